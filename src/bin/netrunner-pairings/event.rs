@@ -0,0 +1,298 @@
+//! Tournament state file
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use netrunner_pairings::{Format, Pairing, Pairings, PairingsError, Player, Result, Round, Side};
+
+use crate::CliResult;
+
+/// Tournament state file contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub name: String,
+    pub date: String,
+    pub pairings: Pairings,
+
+    // format whose card pool registered identities are checked against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<Format>,
+
+    // results reported so far for the current round, by table number
+    #[serde(default)]
+    reported: BTreeMap<usize, Vec<Result>>,
+}
+
+impl Event {
+    pub fn new(name: String, date: String, pairings: Pairings) -> Self {
+        Self {
+            name,
+            date,
+            pairings,
+            format: None,
+            reported: BTreeMap::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> CliResult<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|err| format!("unable to read {}: {}", path.display(), err))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &Path) -> CliResult<()> {
+        // write a temporary file first so a failed save can't lose the event
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Gets the current Swiss or elimination round
+    pub fn current_round(&self) -> Option<&Round> {
+        match self.pairings.get_elimination() {
+            Some(elimination) => elimination.get_rounds().last(),
+            None => self.pairings.get_rounds().last(),
+        }
+    }
+
+    /// Gets the number of games played at a table
+    pub fn games(&self, pairing: &Pairing) -> usize {
+        let single_sided = pairing.get_player_side(pairing.get_player()).is_some()
+            || self.pairings.is_single_sided();
+        if single_sided {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Finds a player by id, or by case-insensitive full name or nickname
+    pub fn find_player(&self, query: &str) -> CliResult<Player> {
+        if let Some(player) = self.pairings.get_player(query) {
+            return Ok(player.clone());
+        }
+
+        let query = query.to_lowercase();
+        let matches = self
+            .pairings
+            .get_players()
+            .iter()
+            .filter(|player| {
+                player.get_full_name().to_lowercase() == query
+                    || player.get_nickname().to_lowercase() == query
+            })
+            .collect::<Vec<_>>();
+
+        match matches.as_slice() {
+            [player] => Ok((*player).clone()),
+            [] => Err(PairingsError::UnknownPlayer(query).into()),
+            _ => Err(format!("more than one player is named {}", query).into()),
+        }
+    }
+
+    pub fn add_player(&mut self, player: Player) -> CliResult<()> {
+        if self.pairings.get_rounds().is_empty() {
+            self.pairings.register_player(player)?;
+        } else {
+            self.pairings.late_join(player)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the title of the current Swiss or elimination round
+    pub fn round_title(&self) -> String {
+        match self.pairings.get_elimination() {
+            Some(elimination) => format!("Elimination round {}", elimination.get_rounds().len()),
+            None => format!("Round {}", self.pairings.get_rounds().len()),
+        }
+    }
+
+    /// Gets the results reported for a table in the current round, if any
+    #[cfg(any(feature = "tui", feature = "server"))]
+    pub fn get_results(&self, table: usize) -> Option<Vec<Result>> {
+        let pairing = self
+            .current_round()?
+            .get_pairings()
+            .get(table.checked_sub(1)?)?;
+        if !pairing.get_games().is_empty() {
+            return Some(pairing.get_results());
+        }
+        self.reported.get(&table).cloned()
+    }
+
+    /// Checks if the next round can be paired
+    #[cfg(any(feature = "tui", feature = "server"))]
+    pub fn can_pair(&self) -> bool {
+        let finished = self
+            .pairings
+            .get_elimination()
+            .is_some_and(|elimination| elimination.is_finished());
+        let round_ended = self.current_round().is_none_or(Round::has_ended);
+        round_ended && !finished
+    }
+
+    pub fn pair(&mut self, side: Side) -> CliResult<Vec<Pairing>> {
+        let pairings = match self.pairings.get_elimination_mut() {
+            Some(elimination) => elimination.next_round(|_, _| side)?,
+            None => self.pairings.next_round()?,
+        };
+        self.reported.clear();
+        Ok(pairings)
+    }
+
+    /// Records the results for a table, ending the round once every table is reported
+    ///
+    /// Returns true if the round ended
+    pub fn report(&mut self, table: usize, results: Vec<Result>) -> CliResult<bool> {
+        let round = self.current_round().ok_or(PairingsError::RoundNotStarted)?;
+        if round.has_ended() {
+            return Err(PairingsError::RoundAlreadyEnded.into());
+        }
+
+        let pairing = table
+            .checked_sub(1)
+            .and_then(|idx| round.get_pairings().get(idx))
+            .ok_or_else(|| format!("there is no table {}", table))?;
+        if pairing.get_opponent().is_none() {
+            return Err(format!("table {} is a bye", table).into());
+        }
+
+        let games = self.games(pairing);
+        if results.len() != games {
+            return Err(format!("table {} needs {} results", table, games).into());
+        }
+
+        let pairings = round.get_pairings().clone();
+        self.reported.insert(table, results);
+
+        let complete = pairings.iter().enumerate().all(|(idx, pairing)| {
+            pairing.get_opponent().is_none() || self.reported.contains_key(&(idx + 1))
+        });
+        if !complete {
+            return Ok(false);
+        }
+
+        let results = pairings
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, pairing)| match self.reported.get(&(idx + 1)) {
+                Some(results) => results
+                    .iter()
+                    .map(|result| (pairing, *result))
+                    .collect::<Vec<_>>(),
+                None => vec![(pairing, Result::Bye); self.games(pairing)],
+            })
+            .collect::<Vec<_>>();
+
+        let ended = match self.pairings.get_elimination_mut() {
+            Some(elimination) => elimination.round_ended(results),
+            None => self.pairings.round_ended(results),
+        };
+        if let Err(err) = ended {
+            // the table can be reported again once it's fixed
+            self.reported.remove(&table);
+            return Err(err.into());
+        }
+        self.reported.clear();
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(players: usize) -> Event {
+        let mut event = Event::new(
+            "Test".to_owned(),
+            "2023-01-01".to_owned(),
+            Pairings::new_swiss().with_seed(0),
+        );
+        for idx in 0..players {
+            event
+                .add_player(Player::new("first", format!("last{}", idx), None))
+                .unwrap();
+        }
+        event
+    }
+
+    #[test]
+    fn find_player() {
+        let mut event = event(2);
+        let player = event.pairings.get_players()[0].clone();
+        assert_eq!(event.find_player(player.get_id()).unwrap(), player);
+        assert_eq!(event.find_player("FIRST LAST0").unwrap(), player);
+        assert!(event.find_player("nobody").is_err());
+
+        event
+            .add_player(Player::new("first", "last0", None))
+            .unwrap();
+        assert!(event.find_player("first last0").is_err());
+    }
+
+    #[test]
+    fn report() {
+        let mut event = event(3);
+        let pairings = event.pair(Side::Corp).unwrap();
+        let (table, bye) = if pairings[0].get_opponent().is_some() {
+            (1, 2)
+        } else {
+            (2, 1)
+        };
+
+        assert!(event.report(bye, vec![Result::Win]).is_err());
+        assert!(event.report(table, vec![Result::Win]).is_err());
+        assert!(event.report(3, vec![Result::Win, Result::Win]).is_err());
+
+        assert!(event
+            .report(table, vec![Result::Win, Result::Draw])
+            .unwrap());
+        assert!(event.reported.is_empty());
+        assert!(event.current_round().unwrap().has_ended());
+
+        let standings = event.pairings.standings().unwrap();
+        assert_eq!(standings[0].get_score(), 6);
+        assert_eq!(standings[1].get_score(), 4);
+        assert_eq!(standings[2].get_score(), 1);
+    }
+
+    #[test]
+    fn report_elimination() {
+        let mut event = event(16);
+        let pairings = event.pair(Side::Corp).unwrap();
+        for table in 1..=pairings.len() {
+            event
+                .report(table, vec![Result::Win, Result::Loss])
+                .unwrap();
+        }
+
+        event.pairings.start_top_cut().unwrap();
+        let pairings = event.pair(Side::Runner).unwrap();
+        assert_eq!(pairings.len(), 2);
+        assert!(event.report(1, vec![Result::Win, Result::Win]).is_err());
+
+        assert!(!event.report(1, vec![Result::Win]).unwrap());
+        assert_eq!(event.reported.len(), 1);
+
+        // a drawn elimination game can't end the round, and isn't kept
+        assert!(event.report(2, vec![Result::Draw]).is_err());
+        assert_eq!(event.reported.len(), 1);
+        assert!(event.get_results(2).is_none());
+
+        assert!(event.report(2, vec![Result::Loss]).unwrap());
+        assert_eq!(
+            event
+                .pairings
+                .get_elimination()
+                .unwrap()
+                .get_remaining_players()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,758 @@
+//! Command-line tournament runner
+//!
+//! Every command loads the tournament state file, updates it, and saves it again,
+//! so an event can be run entirely offline from a terminal.
+
+mod event;
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "tui")]
+mod tui;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use netrunner_pairings::cobra::CobraTournament;
+use netrunner_pairings::meta::{MetaReport, MetaTable};
+use netrunner_pairings::nrtm::NrtmTournament;
+use netrunner_pairings::ratings::Ratings;
+use netrunner_pairings::{
+    CardDatabase, Decklist, EliminationFormat, Faction, Format, Identity, LateJoinScoring, Pairing,
+    Pairings, PairingsError, Player, Result, ScoringSystem, Side,
+};
+
+use event::Event;
+
+type CliResult<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Runs Netrunner tournaments from the terminal")]
+struct Cli {
+    /// Tournament state file
+    #[arg(short, long, default_value = "tournament.json")]
+    file: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Creates a new tournament
+    New {
+        /// Tournament name
+        name: String,
+
+        /// Tournament date, such as 2023-01-31
+        #[arg(long, default_value = "")]
+        date: String,
+
+        /// Play single-sided Swiss rounds
+        #[arg(long)]
+        single_sided: bool,
+
+        /// Top cut format
+        #[arg(long, value_enum, default_value_t = CutFormat::Single)]
+        cut: CutFormat,
+
+        /// Score rounds missed by late players as byes instead of losses
+        #[arg(long)]
+        late_join_byes: bool,
+
+        /// Points for each game of a bye, instead of a win's points
+        #[arg(long)]
+        bye_points: Option<u64>,
+
+        /// Points for each game of an intentional draw, instead of a draw's points
+        #[arg(long)]
+        intentional_draw_points: Option<u64>,
+
+        /// Points for a game won on time, instead of a win's points
+        #[arg(long)]
+        timed_win_points: Option<u64>,
+
+        /// Format whose card pool identities are checked against, such as standard
+        #[arg(long)]
+        format: Option<Format>,
+
+        /// Seed for every random pairing decision
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Replace an existing tournament state file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Registers a player, or adds them late once the tournament has started
+    AddPlayer {
+        first_name: String,
+        last_name: String,
+
+        #[arg(long)]
+        nickname: Option<String>,
+
+        /// Id to register the player with, such as to rate the same player across a league's events
+        #[arg(long)]
+        id: Option<String>,
+
+        /// Corp identity, instead of the one named in the Corp decklist
+        #[arg(long)]
+        corp: Option<String>,
+
+        /// Faction of the Corp identity, such as haas-bioroid
+        #[arg(long)]
+        corp_faction: Option<Faction>,
+
+        /// NetrunnerDB text or JSON Corp decklist file
+        #[arg(long)]
+        corp_deck: Option<PathBuf>,
+
+        /// Runner identity, instead of the one named in the Runner decklist
+        #[arg(long)]
+        runner: Option<String>,
+
+        /// Faction of the Runner identity, such as shaper
+        #[arg(long)]
+        runner_faction: Option<Faction>,
+
+        /// NetrunnerDB text or JSON Runner decklist file
+        #[arg(long)]
+        runner_deck: Option<PathBuf>,
+
+        /// Directory with NetrunnerDB cards, packs, cycles and MWL dumps to check
+        /// the identities and decklists against, for tournaments with a format
+        #[arg(long)]
+        cards: Option<PathBuf>,
+    },
+
+    /// Drops a player, given their id or name
+    Drop { player: String },
+
+    /// Checks every registered decklist against the format's rules before round one
+    CheckDecks {
+        /// Format to check against, instead of the tournament's format
+        #[arg(long)]
+        format: Option<Format>,
+
+        /// Directory with NetrunnerDB cards, packs, cycles and MWL dumps to check cards against
+        #[arg(long)]
+        cards: PathBuf,
+    },
+
+    /// Pairs the next Swiss or elimination round
+    Pair {
+        /// Side taken by the higher seed when they get to choose in elimination rounds
+        #[arg(long, value_enum, default_value_t = SideArg::Corp)]
+        side: SideArg,
+    },
+
+    /// Reports a table in the current round
+    ///
+    /// Results are from the point of view of the first player at the table,
+    /// one per game. Double-sided Swiss tables report the game where
+    /// the first player is the Corp first.
+    Report {
+        table: usize,
+
+        #[arg(value_enum, required = true)]
+        results: Vec<GameResult>,
+    },
+
+    /// Shows the Swiss standings and the top cut placements
+    Standings,
+
+    /// Starts the top cut from the current standings
+    Cut,
+
+    /// Exports the tournament for results sites
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Nrtm)]
+        format: ExportFormat,
+
+        /// Output file, instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Reports identity and faction representation and win rates, for Swiss and the top cut
+    Meta {
+        #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+
+        /// Table to write in the CSV format
+        #[arg(long, value_enum, default_value_t = TableArg::Identities)]
+        table: TableArg,
+
+        /// Output file, instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Rates players by replaying tournament files in order, such as a league's events
+    Rate {
+        /// Tournament state files, instead of the tournament file
+        events: Vec<PathBuf>,
+
+        #[arg(long, value_enum, default_value_t = RatingArg::Glicko2, conflicts_with = "ratings")]
+        system: RatingArg,
+
+        /// Keep separate Corp and Runner ratings
+        #[arg(long, conflicts_with = "ratings")]
+        separate_sides: bool,
+
+        /// Saved ratings file to update with its own rating system, instead of new ratings
+        #[arg(long)]
+        ratings: Option<PathBuf>,
+
+        /// Ratings file to save, as well as showing the ratings, instead of the ratings file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Serves every tournament in a data directory over an HTTP/JSON API
+    #[cfg(feature = "server")]
+    Serve {
+        /// Directory the tournaments are saved in
+        #[arg(long, default_value = "tournaments")]
+        dir: PathBuf,
+
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: SocketAddr,
+    },
+
+    /// Runs the event from an interactive terminal UI
+    #[cfg(feature = "tui")]
+    Tui,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum CutFormat {
+    Single,
+    Double,
+}
+
+impl From<CutFormat> for EliminationFormat {
+    fn from(format: CutFormat) -> Self {
+        match format {
+            CutFormat::Single => Self::Single,
+            CutFormat::Double => Self::Double,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum SideArg {
+    Corp,
+    Runner,
+}
+
+impl From<SideArg> for Side {
+    fn from(side: SideArg) -> Self {
+        match side {
+            SideArg::Corp => Self::Corp,
+            SideArg::Runner => Self::Runner,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum GameResult {
+    #[value(alias = "w")]
+    Win,
+
+    #[value(alias = "l")]
+    Loss,
+
+    #[value(alias = "d")]
+    Draw,
+
+    #[value(alias = "id")]
+    IntentionalDraw,
+
+    #[value(alias = "tw")]
+    TimedWin,
+
+    #[value(alias = "tl")]
+    TimedLoss,
+}
+
+impl From<GameResult> for Result {
+    fn from(result: GameResult) -> Self {
+        match result {
+            GameResult::Win => Self::Win,
+            GameResult::Loss => Self::Loss,
+            GameResult::Draw => Self::Draw,
+            GameResult::IntentionalDraw => Self::IntentionalDraw,
+            GameResult::TimedWin => Self::TimedWin,
+            GameResult::TimedLoss => Self::TimedLoss,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    Nrtm,
+    Cobra,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    Markdown,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum RatingArg {
+    Elo,
+    Glicko2,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum TableArg {
+    Identities,
+    Factions,
+    Matchups,
+}
+
+impl From<TableArg> for MetaTable {
+    fn from(table: TableArg) -> Self {
+        match table {
+            TableArg::Identities => Self::Identities,
+            TableArg::Factions => Self::Factions,
+            TableArg::Matchups => Self::Matchups,
+        }
+    }
+}
+
+/// Reads a NetrunnerDB decklist file, in either the text or JSON format
+fn read_decklist(path: &Path) -> CliResult<Decklist> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("unable to read {}: {}", path.display(), err))?;
+    let decklist = if contents.trim_start().starts_with('{') {
+        Decklist::from_json(&contents)?
+    } else {
+        Decklist::from_text(&contents)?
+    };
+    Ok(decklist)
+}
+
+/// Reads the NetrunnerDB dumps in a directory, checking they define the format
+fn read_cards(dir: &Path, format: Format) -> CliResult<CardDatabase> {
+    let read = |name: &str| {
+        let path = dir.join(name);
+        fs::read_to_string(&path)
+            .map_err(|err| format!("unable to read {}: {}", path.display(), err))
+    };
+    let database = CardDatabase::from_netrunnerdb(
+        &read("cards.json")?,
+        &read("packs.json")?,
+        &read("cycles.json")?,
+        &read("mwl.json")?,
+    )?;
+    if !database.has_format(format) {
+        return Err(PairingsError::UndefinedFormat(format).into());
+    }
+    Ok(database)
+}
+
+fn print_ratings(ratings: &Ratings, names: &HashMap<String, String>) {
+    println!(
+        "{:>4}  {:<24} {:>7} {:>5} {:>5}",
+        "Rank", "Player", "Rating", "RD", "Games"
+    );
+    for (idx, (id, rating)) in ratings.get_rated_players().into_iter().enumerate() {
+        println!(
+            "{:>4}  {:<24} {:>7.0} {:>5.0} {:>5}",
+            idx + 1,
+            names.get(id).unwrap_or(id),
+            rating.get_rating(),
+            rating.get_deviation(),
+            rating.get_games()
+        );
+    }
+}
+
+fn print_pairings(title: &str, pairings: &[Pairing]) {
+    println!("{}", title);
+    for (idx, pairing) in pairings.iter().enumerate() {
+        let player = pairing.get_player();
+        match pairing.get_opponent() {
+            Some(opponent) => match pairing.get_player_side(player) {
+                Some(side) => println!(
+                    "  Table {}: {} ({:?}) vs {} ({:?})",
+                    idx + 1,
+                    player.get_nickname(),
+                    side,
+                    opponent.get_nickname(),
+                    side.opposite()
+                ),
+                None => println!(
+                    "  Table {}: {} vs {}",
+                    idx + 1,
+                    player.get_nickname(),
+                    opponent.get_nickname()
+                ),
+            },
+            None => println!("  Table {}: {} has a bye", idx + 1, player.get_nickname()),
+        }
+    }
+}
+
+fn print_standings(event: &Event) -> CliResult<()> {
+    let standings = event.pairings.standings()?;
+    let width = standings
+        .iter()
+        .map(|standing| standing.get_player().get_nickname().len())
+        .max()
+        .unwrap_or(0)
+        .max("Player".len());
+
+    println!(
+        "{:>4}  {:width$}  {:>5}  {:>6}  {:>6}  {:9}  {:9}  W-L-D-B",
+        "Rank", "Player", "Score", "SoS", "ESoS", "Corp", "Runner"
+    );
+    for standing in &standings {
+        let player = standing.get_player();
+        println!(
+            "{:>4}  {:width$}  {:>5}  {:>6.3}  {:>6.3}  {:9}  {:9}  {}{}{}",
+            standing.get_rank(),
+            player.get_nickname(),
+            standing.get_score(),
+            standing.get_sos(),
+            standing.get_esos(),
+            standing.get_corp_record().to_string(),
+            standing.get_runner_record().to_string(),
+            standing.get_record(),
+            if standing.is_top_cut() { "  cut" } else { "" },
+            if event.pairings.is_dropped(player) {
+                "  dropped"
+            } else {
+                ""
+            },
+        );
+    }
+
+    if let Some(elimination) = event.pairings.get_elimination() {
+        println!();
+        println!("Top cut");
+        for placement in elimination.get_placements() {
+            println!(
+                "{:>7}  {}",
+                placement.to_string(),
+                placement.get_player().get_nickname()
+            );
+        }
+        for player in elimination.get_remaining_players() {
+            if elimination.get_champion() != Some(player) {
+                println!(
+                    "{:>7}  {}",
+                    format!("seed {}", elimination.get_seed(player).unwrap()),
+                    player.get_nickname()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run(cli: Cli) -> CliResult<()> {
+    if let Command::New {
+        name,
+        date,
+        single_sided,
+        cut,
+        late_join_byes,
+        bye_points,
+        intentional_draw_points,
+        timed_win_points,
+        format,
+        seed,
+        force,
+    } = cli.command
+    {
+        if cli.file.exists() && !force {
+            return Err(format!(
+                "{} already exists, use --force to replace it",
+                cli.file.display()
+            )
+            .into());
+        }
+
+        let mut pairings = if single_sided {
+            Pairings::new_single_swiss()
+        } else {
+            Pairings::new_swiss()
+        }
+        .with_elimination_format(cut.into());
+        if late_join_byes {
+            pairings = pairings.with_late_join_scoring(LateJoinScoring::Bye);
+        }
+        if let Some(seed) = seed {
+            pairings = pairings.with_seed(seed);
+        }
+
+        let mut scoring = ScoringSystem::standard();
+        if let Some(points) = bye_points {
+            scoring = scoring.with_bye_points(points);
+        }
+        if let Some(points) = intentional_draw_points {
+            scoring = scoring.with_intentional_draw_points(points);
+        }
+        if let Some(points) = timed_win_points {
+            scoring = scoring.with_timed_win_points(points, scoring.get_points(Result::TimedLoss));
+        }
+        pairings = pairings.with_scoring_system(scoring);
+
+        let mut event = Event::new(name, date, pairings);
+        event.format = format;
+        event.save(&cli.file)?;
+        println!("Created {}", cli.file.display());
+        return Ok(());
+    }
+
+    if let Command::Rate {
+        events,
+        system,
+        separate_sides,
+        ratings: saved,
+        output,
+    } = cli.command
+    {
+        let mut ratings = match &saved {
+            Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+            None => {
+                let ratings = match system {
+                    RatingArg::Elo => Ratings::new_elo(),
+                    RatingArg::Glicko2 => Ratings::new_glicko2(),
+                };
+                if separate_sides {
+                    ratings.with_separate_sides()
+                } else {
+                    ratings
+                }
+            }
+        };
+
+        let events = if events.is_empty() {
+            vec![cli.file]
+        } else {
+            events
+        };
+        let mut names = HashMap::new();
+        for path in &events {
+            let event = Event::load(path)?;
+            ratings.add_tournament(&event.pairings);
+            for player in event.pairings.get_players() {
+                names.insert(player.get_id().clone(), player.get_nickname());
+            }
+        }
+
+        print_ratings(&ratings, &names);
+        if let Some(output) = output.or(saved) {
+            fs::write(output, serde_json::to_string_pretty(&ratings)?)?;
+        }
+        return Ok(());
+    }
+
+    #[cfg(feature = "server")]
+    if let Command::Serve { dir, addr } = &cli.command {
+        return server::run(dir, *addr);
+    }
+
+    #[cfg(feature = "tui")]
+    if let Command::Tui = cli.command {
+        return tui::run(&cli.file);
+    }
+
+    let mut event = Event::load(&cli.file)?;
+    match cli.command {
+        Command::New { .. } | Command::Rate { .. } => unreachable!(),
+        #[cfg(feature = "server")]
+        Command::Serve { .. } => unreachable!(),
+        #[cfg(feature = "tui")]
+        Command::Tui => unreachable!(),
+        Command::AddPlayer {
+            first_name,
+            last_name,
+            nickname,
+            id,
+            corp,
+            corp_faction,
+            corp_deck,
+            runner,
+            runner_faction,
+            runner_deck,
+            cards,
+        } => {
+            // cards are only checked for events with a format, against full card data
+            let database = match (event.format, cards) {
+                (Some(format), Some(path)) => Some((format, read_cards(&path, format)?)),
+                (Some(_), None) => {
+                    println!("Cards not checked, use --cards with NetrunnerDB dumps to check them");
+                    None
+                }
+                (None, _) => None,
+            };
+
+            let mut player = match id {
+                Some(id) => Player::new_with_id(id, first_name, last_name, nickname),
+                None => Player::new(first_name, last_name, nickname),
+            };
+            let mut decklists = vec![];
+            for (side, identity, faction, deck) in [
+                (Side::Corp, corp, corp_faction, corp_deck),
+                (Side::Runner, runner, runner_faction, runner_deck),
+            ] {
+                let mut decklist = deck.as_deref().map(read_decklist).transpose()?;
+                if let (Some((_, database)), Some(deck)) = (&database, &decklist) {
+                    decklist = Some(database.resolve_decklist(deck)?);
+                }
+
+                let identity = identity.or_else(|| decklist.as_ref()?.get_identity().cloned());
+                if let Some(identity) = identity {
+                    let mut identity = Identity::new(identity);
+                    if let Some(faction) = faction {
+                        identity = identity.with_faction(faction);
+                    }
+                    if let Some((format, database)) = &database {
+                        identity = database.check_identity(&identity, side, *format)?;
+                    }
+                    player = player.with_identity(side, identity);
+                }
+                decklists.extend(decklist.map(|decklist| (side, decklist)));
+            }
+
+            event.add_player(player.clone())?;
+            for (side, decklist) in decklists {
+                event.pairings.set_decklist(&player, side, decklist)?;
+            }
+            println!("Added {} ({})", player.get_nickname(), player.get_id());
+        }
+        Command::Drop { player } => {
+            let player = event.find_player(&player)?;
+            event.pairings.drop_player(&player)?;
+            println!("Dropped {}", player.get_nickname());
+        }
+        Command::CheckDecks { format, cards } => {
+            let format = format
+                .or(event.format)
+                .ok_or("the tournament has no format, use --format to choose one")?;
+            let database = read_cards(&cards, format)?;
+
+            let violations = event.pairings.check_decklists(&database, format);
+            for violation in &violations {
+                let player = event
+                    .pairings
+                    .get_player(violation.get_player())
+                    .map_or_else(|| violation.get_player().clone(), Player::get_nickname);
+                println!(
+                    "{} ({:?}): {}",
+                    player,
+                    violation.get_side(),
+                    violation.get_violation()
+                );
+            }
+            if !violations.is_empty() {
+                return Err(format!("{} deck violations", violations.len()).into());
+            }
+            println!("Every deck is legal in {}", format);
+            return Ok(());
+        }
+        Command::Pair { side } => {
+            let pairings = event.pair(side.into())?;
+            print_pairings(&event.round_title(), &pairings);
+        }
+        Command::Report { table, results } => {
+            let results = results.into_iter().map(Result::from).collect();
+            if event.report(table, results)? {
+                println!("Round complete");
+                if let Some(champion) = event
+                    .pairings
+                    .get_elimination()
+                    .and_then(|elimination| elimination.get_champion())
+                {
+                    println!("{} wins the tournament", champion.get_nickname());
+                }
+            } else {
+                println!("Reported table {}", table);
+            }
+        }
+        Command::Standings => return print_standings(&event),
+        Command::Cut => {
+            let elimination = event.pairings.start_top_cut()?;
+            println!("Top {} cut", elimination.get_seeds().len());
+            for (idx, player) in elimination.get_seeds().iter().enumerate() {
+                println!("  Seed {}: {}", idx + 1, player.get_nickname());
+            }
+        }
+        Command::Export { format, output } => {
+            let json = match format {
+                ExportFormat::Nrtm => {
+                    NrtmTournament::from_pairings(&event.pairings, &event.name, &event.date)?
+                        .to_json()?
+                }
+                ExportFormat::Cobra => {
+                    CobraTournament::from_pairings(&event.pairings, &event.name, &event.date)?
+                        .to_json()?
+                }
+            };
+            match output {
+                Some(output) => fs::write(output, json)?,
+                None => writeln!(io::stdout(), "{}", json)?,
+            }
+            return Ok(());
+        }
+        Command::Meta {
+            format,
+            table,
+            output,
+        } => {
+            let report = MetaReport::from_pairings(&event.pairings);
+            let contents = match format {
+                ReportFormat::Markdown => report.to_markdown(),
+                ReportFormat::Csv => report.to_csv(table.into()),
+                ReportFormat::Json => report.to_json()?,
+            };
+            match output {
+                Some(output) => fs::write(output, contents)?,
+                None => write!(io::stdout(), "{}", contents)?,
+            }
+            return Ok(());
+        }
+    }
+
+    event.save(&cli.file)
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+}
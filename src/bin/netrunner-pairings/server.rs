@@ -0,0 +1,590 @@
+//! HTTP/JSON API for running events from other devices
+//!
+//! Every tournament is held in memory and saved to its own file in the data directory
+//! after each change, so the server can be restarted mid-event.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path as UrlPath, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use netrunner_pairings::{
+    EliminationFormat, Identity, LateJoinScoring, Pairing, Pairings, PairingsError, Player, Result,
+    ScoringSystem, Side, Standing,
+};
+
+use crate::event::Event;
+use crate::CliResult;
+
+/// Tournaments being served, by id
+struct Store {
+    dir: PathBuf,
+    events: Mutex<BTreeMap<String, Event>>,
+}
+
+impl Store {
+    /// Loads every tournament saved in the data directory
+    fn load(dir: &Path) -> CliResult<Self> {
+        fs::create_dir_all(dir)?;
+
+        let mut events = BTreeMap::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    events.insert(id.to_owned(), Event::load(&path)?);
+                }
+            }
+        }
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            events: Mutex::new(events),
+        })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    /// Runs an update on a tournament and saves it
+    ///
+    /// The update runs on a copy of the tournament, which replaces it only once
+    /// it has been saved, so a failed update or save leaves the tournament unchanged
+    fn update<T>(&self, id: &str, update: impl FnOnce(&mut Event) -> CliResult<T>) -> ApiResult<T> {
+        let mut events = self.events.lock().unwrap();
+        let event = events.get_mut(id).ok_or_else(|| ApiError::not_found(id))?;
+
+        let mut updated = event.clone();
+        let value = update(&mut updated)?;
+        updated.save(&self.path(id))?;
+        *event = updated;
+        Ok(value)
+    }
+
+    /// Reads from a tournament
+    fn read<T>(&self, id: &str, read: impl FnOnce(&Event) -> CliResult<T>) -> ApiResult<T> {
+        let events = self.events.lock().unwrap();
+        let event = events.get(id).ok_or_else(|| ApiError::not_found(id))?;
+        Ok(read(event)?)
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// Error response, returned as `{"error": "..."}`
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn not_found(id: &str) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: format!("there is no tournament {}", id),
+        }
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for ApiError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        // pairing errors come from the tournament's state, anything else from the request
+        let status = match err.downcast_ref::<PairingsError>() {
+            Some(PairingsError::UnknownPlayer(_)) => StatusCode::NOT_FOUND,
+            Some(_) => StatusCode::CONFLICT,
+            None => StatusCode::BAD_REQUEST,
+        };
+        Self {
+            status,
+            message: err.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NewTournament {
+    name: String,
+    #[serde(default)]
+    date: String,
+    #[serde(default)]
+    single_sided: bool,
+    #[serde(default)]
+    cut: EliminationFormat,
+    #[serde(default)]
+    late_join_byes: bool,
+    #[serde(default)]
+    scoring: ScoringSystem,
+    seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct TournamentSummary {
+    id: String,
+    name: String,
+    date: String,
+    players: usize,
+    round: Option<String>,
+    can_pair: bool,
+}
+
+impl TournamentSummary {
+    fn new(id: &str, event: &Event) -> Self {
+        Self {
+            id: id.to_owned(),
+            name: event.name.clone(),
+            date: event.date.clone(),
+            players: event.pairings.get_players().len(),
+            round: event.current_round().map(|_| event.round_title()),
+            can_pair: event.can_pair(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NewPlayer {
+    // stable id, such as to rate the same player across a league's events
+    #[serde(default)]
+    id: Option<String>,
+    first_name: String,
+    last_name: String,
+    nickname: Option<String>,
+    corp_identity: Option<Identity>,
+    runner_identity: Option<Identity>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PairRequest {
+    // side the higher seed plays in elimination rounds when sides are chosen
+    #[serde(default)]
+    side: Option<Side>,
+}
+
+#[derive(Debug, Serialize)]
+struct RoundPairings {
+    round: String,
+    ended: bool,
+    pairings: Vec<TablePairing>,
+}
+
+#[derive(Debug, Serialize)]
+struct TablePairing {
+    table: usize,
+    #[serde(flatten)]
+    pairing: Pairing,
+
+    // results reported so far, including tables waiting for the rest of the round
+    reported: Option<Vec<Result>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportResponse {
+    round_ended: bool,
+}
+
+async fn list_tournaments(State(store): State<Arc<Store>>) -> Json<Vec<TournamentSummary>> {
+    let events = store.events.lock().unwrap();
+    Json(
+        events
+            .iter()
+            .map(|(id, event)| TournamentSummary::new(id, event))
+            .collect(),
+    )
+}
+
+async fn create_tournament(
+    State(store): State<Arc<Store>>,
+    Json(request): Json<NewTournament>,
+) -> ApiResult<(StatusCode, Json<TournamentSummary>)> {
+    let mut pairings = if request.single_sided {
+        Pairings::new_single_swiss()
+    } else {
+        Pairings::new_swiss()
+    }
+    .with_elimination_format(request.cut)
+    .with_scoring_system(request.scoring);
+    if request.late_join_byes {
+        pairings = pairings.with_late_join_scoring(LateJoinScoring::Bye);
+    }
+    if let Some(seed) = request.seed {
+        pairings = pairings.with_seed(seed);
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let event = Event::new(request.name, request.date, pairings);
+    event.save(&store.path(&id))?;
+
+    let summary = TournamentSummary::new(&id, &event);
+    store.events.lock().unwrap().insert(id, event);
+    Ok((StatusCode::CREATED, Json(summary)))
+}
+
+async fn get_tournament(
+    State(store): State<Arc<Store>>,
+    UrlPath(id): UrlPath<String>,
+) -> ApiResult<Json<TournamentSummary>> {
+    store
+        .read(&id, |event| Ok(TournamentSummary::new(&id, event)))
+        .map(Json)
+}
+
+async fn list_players(
+    State(store): State<Arc<Store>>,
+    UrlPath(id): UrlPath<String>,
+) -> ApiResult<Json<Vec<Player>>> {
+    store
+        .read(&id, |event| Ok(event.pairings.get_players().clone()))
+        .map(Json)
+}
+
+async fn add_player(
+    State(store): State<Arc<Store>>,
+    UrlPath(id): UrlPath<String>,
+    Json(request): Json<NewPlayer>,
+) -> ApiResult<(StatusCode, Json<Player>)> {
+    let mut player = match request.id {
+        Some(id) => {
+            Player::new_with_id(id, request.first_name, request.last_name, request.nickname)
+        }
+        None => Player::new(request.first_name, request.last_name, request.nickname),
+    };
+    if let Some(identity) = request.corp_identity {
+        player = player.with_identity(Side::Corp, identity);
+    }
+    if let Some(identity) = request.runner_identity {
+        player = player.with_identity(Side::Runner, identity);
+    }
+    store.update(&id, |event| event.add_player(player.clone()))?;
+    Ok((StatusCode::CREATED, Json(player)))
+}
+
+async fn drop_player(
+    State(store): State<Arc<Store>>,
+    UrlPath((id, player)): UrlPath<(String, String)>,
+) -> ApiResult<StatusCode> {
+    store.update(&id, |event| {
+        let player = event.find_player(&player)?;
+        Ok(event.pairings.drop_player(&player)?)
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Gets the current round's pairings with their table numbers
+fn round_pairings(event: &Event) -> CliResult<RoundPairings> {
+    let round = event
+        .current_round()
+        .ok_or(PairingsError::RoundNotStarted)?;
+    Ok(RoundPairings {
+        round: event.round_title(),
+        ended: round.has_ended(),
+        pairings: round
+            .get_pairings()
+            .iter()
+            .enumerate()
+            .map(|(idx, pairing)| TablePairing {
+                table: idx + 1,
+                pairing: pairing.clone(),
+                reported: event.get_results(idx + 1),
+            })
+            .collect(),
+    })
+}
+
+async fn pair_round(
+    State(store): State<Arc<Store>>,
+    UrlPath(id): UrlPath<String>,
+    request: Option<Json<PairRequest>>,
+) -> ApiResult<(StatusCode, Json<RoundPairings>)> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let pairings = store.update(&id, |event| {
+        event.pair(request.side.unwrap_or(Side::Corp))?;
+        round_pairings(event)
+    })?;
+    Ok((StatusCode::CREATED, Json(pairings)))
+}
+
+async fn get_pairings(
+    State(store): State<Arc<Store>>,
+    UrlPath(id): UrlPath<String>,
+) -> ApiResult<Json<RoundPairings>> {
+    store.read(&id, round_pairings).map(Json)
+}
+
+async fn report_results(
+    State(store): State<Arc<Store>>,
+    UrlPath((id, table)): UrlPath<(String, usize)>,
+    Json(results): Json<Vec<Result>>,
+) -> ApiResult<Json<ReportResponse>> {
+    let round_ended = store.update(&id, |event| event.report(table, results))?;
+    Ok(Json(ReportResponse { round_ended }))
+}
+
+async fn get_standings(
+    State(store): State<Arc<Store>>,
+    UrlPath(id): UrlPath<String>,
+) -> ApiResult<Json<Vec<Standing>>> {
+    store
+        .read(&id, |event| Ok(event.pairings.standings()?))
+        .map(Json)
+}
+
+fn router(store: Arc<Store>) -> Router {
+    Router::new()
+        .route(
+            "/tournaments",
+            get(list_tournaments).post(create_tournament),
+        )
+        .route("/tournaments/{id}", get(get_tournament))
+        .route(
+            "/tournaments/{id}/players",
+            get(list_players).post(add_player),
+        )
+        .route("/tournaments/{id}/players/{player}", delete(drop_player))
+        .route("/tournaments/{id}/rounds", post(pair_round))
+        .route("/tournaments/{id}/pairings", get(get_pairings))
+        .route(
+            "/tournaments/{id}/tables/{table}/results",
+            post(report_results),
+        )
+        .route("/tournaments/{id}/standings", get(get_standings))
+        .with_state(store)
+}
+
+/// Serves the tournaments saved in a data directory until the process is stopped
+pub fn run(dir: &Path, addr: SocketAddr) -> CliResult<()> {
+    let store = Arc::new(Store::load(dir)?);
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        println!(
+            "Serving {} on http://{}",
+            dir.display(),
+            listener.local_addr()?
+        );
+        axum::serve(listener, router(store)).await?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn store(name: &str) -> Arc<Store> {
+        let dir =
+            std::env::temp_dir().join(format!("netrunner-pairings-{}-{}", name, Uuid::new_v4()));
+        Arc::new(Store::load(&dir).unwrap())
+    }
+
+    async fn request(
+        store: &Arc<Store>,
+        method: &str,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+
+        let response = router(store.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&body).unwrap()
+        };
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn run_tournament() {
+        let store = store("run");
+        let (status, tournament) = request(
+            &store,
+            "POST",
+            "/tournaments",
+            Some(json!({ "name": "Test", "seed": 0 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = tournament["id"].as_str().unwrap().to_owned();
+
+        for idx in 0..3 {
+            let (status, _) = request(
+                &store,
+                "POST",
+                &format!("/tournaments/{}/players", id),
+                Some(json!({ "first_name": "first", "last_name": format!("last{}", idx) })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        // players can be registered with a stable id, which must be unique
+        let player = json!({ "id": "league-1", "first_name": "first", "last_name": "league" });
+        let (status, body) = request(
+            &store,
+            "POST",
+            &format!("/tournaments/{}/players", id),
+            Some(player.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["id"], "league-1");
+        let (status, _) = request(
+            &store,
+            "POST",
+            &format!("/tournaments/{}/players", id),
+            Some(player),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, round) =
+            request(&store, "POST", &format!("/tournaments/{}/rounds", id), None).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(round["round"], "Round 1");
+        assert_eq!(round["pairings"].as_array().unwrap().len(), 2);
+
+        let (status, report) = request(
+            &store,
+            "POST",
+            &format!("/tournaments/{}/tables/1/results", id),
+            Some(json!(["Win", "Win"])),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report["round_ended"], false);
+
+        // the next round can't be paired until every table is reported
+        let (status, _) =
+            request(&store, "POST", &format!("/tournaments/{}/rounds", id), None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (_, report) = request(
+            &store,
+            "POST",
+            &format!("/tournaments/{}/tables/2/results", id),
+            Some(json!(["Win", "Loss"])),
+        )
+        .await;
+        assert_eq!(report["round_ended"], true);
+
+        let (status, standings) = request(
+            &store,
+            "GET",
+            &format!("/tournaments/{}/standings", id),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(standings.as_array().unwrap().len(), 4);
+        assert_eq!(standings[0]["score"], 6);
+
+        // the tournament is loaded again after a restart
+        let reloaded = Store::load(&store.dir).unwrap();
+        reloaded
+            .read(&id, |event| {
+                assert_eq!(event.pairings.get_rounds().len(), 1);
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_update() {
+        let store = store("failed");
+        let (_, tournament) = request(
+            &store,
+            "POST",
+            "/tournaments",
+            Some(json!({ "name": "Test" })),
+        )
+        .await;
+        let id = tournament["id"].as_str().unwrap();
+
+        let result = store.update(id, |event| -> CliResult<()> {
+            event.name = "Changed".to_owned();
+            Err("failed".into())
+        });
+        assert!(result.is_err());
+        store
+            .read(id, |event| {
+                assert_eq!(event.name, "Test");
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let store = store("errors");
+        let (status, body) = request(&store, "GET", "/tournaments/missing/standings", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "there is no tournament missing");
+
+        let (_, tournament) = request(
+            &store,
+            "POST",
+            "/tournaments",
+            Some(json!({ "name": "Test" })),
+        )
+        .await;
+        let id = tournament["id"].as_str().unwrap();
+
+        let (status, _) = request(
+            &store,
+            "GET",
+            &format!("/tournaments/{}/pairings", id),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, _) = request(
+            &store,
+            "DELETE",
+            &format!("/tournaments/{}/players/nobody", id),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = request(
+            &store,
+            "POST",
+            &format!("/tournaments/{}/tables/1/results", id),
+            Some(json!(["Win", "Win"])),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+}
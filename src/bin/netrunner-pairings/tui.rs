@@ -0,0 +1,512 @@
+//! Interactive terminal UI for running a live event
+
+use std::path::Path;
+
+use ratatui::crossterm::event::{self, Event as TermEvent, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use netrunner_pairings::{Pairing, Result, ScoringSystem, Side};
+
+use crate::event::Event;
+use crate::CliResult;
+
+/// Formats game results as the points scored by each player, such as 6-0
+fn score(results: &[Result], scoring: &ScoringSystem) -> String {
+    let player = results
+        .iter()
+        .map(|result| scoring.get_points(*result))
+        .sum::<u64>();
+    let opponent = results
+        .iter()
+        .map(|result| scoring.get_points(result.opposite()))
+        .sum::<u64>();
+    format!("{}-{}", player, opponent)
+}
+
+/// Result entry for a single table
+#[derive(Debug)]
+struct ResultForm {
+    table: usize,
+
+    // each game's result from the first player's point of view
+    games: Vec<Option<Result>>,
+
+    // game being edited
+    game: usize,
+}
+
+impl ResultForm {
+    fn new(table: usize, games: usize) -> Self {
+        Self {
+            table,
+            games: vec![None; games],
+            game: 0,
+        }
+    }
+
+    /// Sets the result for the current game and moves to the next one
+    fn set(&mut self, result: Result) {
+        self.games[self.game] = Some(result);
+        self.game = (self.game + 1).min(self.games.len() - 1);
+    }
+
+    /// Sets every game from the common final scores
+    fn set_all(&mut self, results: &[Result]) {
+        if results.len() == self.games.len() {
+            self.games = results.iter().copied().map(Some).collect();
+        }
+    }
+
+    /// Gets the results once every game has one
+    fn results(&self) -> Option<Vec<Result>> {
+        self.games.iter().copied().collect()
+    }
+}
+
+/// TUI state
+struct App {
+    event: Event,
+
+    // selected table in the current round
+    pairings: ListState,
+
+    form: Option<ResultForm>,
+
+    // status line for the last action or error
+    message: String,
+
+    quit: bool,
+}
+
+impl App {
+    fn new(event: Event) -> Self {
+        Self {
+            event,
+            pairings: ListState::default().with_selected(Some(0)),
+            form: None,
+            message: String::new(),
+            quit: false,
+        }
+    }
+
+    fn current_pairings(&self) -> Vec<Pairing> {
+        self.event
+            .current_round()
+            .map(|round| round.get_pairings().clone())
+            .unwrap_or_default()
+    }
+
+    /// Handles a key press
+    ///
+    /// Returns true if the event changed and needs saving
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let result = match self.form.take() {
+            Some(form) => self.handle_form_key(form, key),
+            None => self.handle_main_key(key),
+        };
+
+        match result {
+            Ok(changed) => changed,
+            Err(err) => {
+                self.message = err.to_string();
+                false
+            }
+        }
+    }
+
+    fn handle_main_key(&mut self, key: KeyEvent) -> CliResult<bool> {
+        let tables = self.current_pairings().len();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.pairings.select_previous(),
+            KeyCode::Down | KeyCode::Char('j')
+                if self.pairings.selected().unwrap_or(0) + 1 < tables =>
+            {
+                self.pairings.select_next()
+            }
+            KeyCode::Enter => {
+                let pairings = self.current_pairings();
+                let table = self.pairings.selected().unwrap_or(0) + 1;
+                let Some(pairing) = pairings.get(table - 1) else {
+                    return Ok(false);
+                };
+
+                if self
+                    .event
+                    .current_round()
+                    .is_some_and(|round| round.has_ended())
+                {
+                    self.message = "The round has already ended".to_owned();
+                } else if pairing.get_opponent().is_none() {
+                    self.message = format!("Table {} is a bye", table);
+                } else {
+                    self.form = Some(ResultForm::new(table, self.event.games(pairing)));
+                }
+            }
+            KeyCode::Char('p') => {
+                if !self.event.can_pair() {
+                    self.message = "Report every table before pairing the next round".to_owned();
+                    return Ok(false);
+                }
+
+                // the higher seed takes Corp when they get to choose in elimination rounds
+                self.event.pair(Side::Corp)?;
+                self.pairings.select(Some(0));
+                self.message = format!("Paired {}", self.event.round_title());
+                return Ok(true);
+            }
+            _ => (),
+        }
+
+        Ok(false)
+    }
+
+    fn handle_form_key(&mut self, mut form: ResultForm, key: KeyEvent) -> CliResult<bool> {
+        match key.code {
+            KeyCode::Esc => return Ok(false),
+            KeyCode::Left | KeyCode::Up => form.game = form.game.saturating_sub(1),
+            KeyCode::Right | KeyCode::Down | KeyCode::Tab => {
+                form.game = (form.game + 1).min(form.games.len() - 1)
+            }
+            KeyCode::Char('w') => form.set(Result::Win),
+            KeyCode::Char('l') => form.set(Result::Loss),
+            KeyCode::Char('d') => form.set(Result::Draw),
+            KeyCode::Char('6') => form.set_all(&[Result::Win, Result::Win]),
+            KeyCode::Char('3') => form.set_all(&[Result::Win, Result::Loss]),
+            KeyCode::Char('0') => form.set_all(&[Result::Loss, Result::Loss]),
+            KeyCode::Enter => {
+                if let Some(results) = form.results() {
+                    let table = form.table;
+                    let round_ended = match self.event.report(table, results) {
+                        Ok(round_ended) => round_ended,
+                        Err(err) => {
+                            self.form = Some(form);
+                            return Err(err);
+                        }
+                    };
+
+                    self.message = if round_ended {
+                        format!("{} complete", self.event.round_title())
+                    } else {
+                        format!("Reported table {}", table)
+                    };
+                    return Ok(true);
+                }
+            }
+            _ => (),
+        }
+
+        self.form = Some(form);
+        Ok(false)
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [title, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+
+        let round = if self.event.current_round().is_some() {
+            self.event.round_title()
+        } else {
+            "Registration".to_owned()
+        };
+        frame.render_widget(
+            Line::from(format!("{} - {}", self.event.name, round)).bold(),
+            title,
+        );
+
+        let [players, pairings, standings] = Layout::horizontal([
+            Constraint::Percentage(25),
+            Constraint::Percentage(45),
+            Constraint::Percentage(30),
+        ])
+        .areas(body);
+        self.render_players(frame, players);
+        self.render_pairings(frame, pairings);
+        self.render_standings(frame, standings);
+        self.render_footer(frame, footer);
+
+        if let Some(form) = &self.form {
+            self.render_form(frame, form);
+        }
+    }
+
+    fn render_players(&self, frame: &mut Frame, area: Rect) {
+        let players = self.event.pairings.get_players().iter().map(|player| {
+            if self.event.pairings.is_dropped(player) {
+                ListItem::new(format!("{} (dropped)", player.get_nickname())).dark_gray()
+            } else {
+                ListItem::new(player.get_nickname())
+            }
+        });
+        frame.render_widget(
+            List::new(players).block(Block::bordered().title("Players")),
+            area,
+        );
+    }
+
+    fn render_pairings(&mut self, frame: &mut Frame, area: Rect) {
+        let pairings = self.current_pairings();
+        let items = pairings.iter().enumerate().map(|(idx, pairing)| {
+            let table = idx + 1;
+            let player = pairing.get_player();
+            let side = |side: Option<Side>| {
+                side.map(|side| format!(" ({:?})", side))
+                    .unwrap_or_default()
+            };
+
+            let Some(opponent) = pairing.get_opponent() else {
+                return ListItem::new(format!("{:>3}  {} has a bye", table, player.get_nickname()));
+            };
+
+            let text = format!(
+                "{:>3}  {}{} vs {}{}",
+                table,
+                player.get_nickname(),
+                side(pairing.get_player_side(player)),
+                opponent.get_nickname(),
+                side(pairing.get_player_side(opponent)),
+            );
+            match self.event.get_results(table) {
+                Some(results) => ListItem::new(Line::from(vec![
+                    Span::raw(text),
+                    Span::raw(format!(
+                        "  {}",
+                        score(&results, self.event.pairings.get_scoring_system())
+                    ))
+                    .green(),
+                ])),
+                None => ListItem::new(text).yellow().add_modifier(Modifier::BOLD),
+            }
+        });
+
+        let list = List::new(items)
+            .block(Block::bordered().title("Pairings"))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.pairings);
+    }
+
+    fn render_standings(&self, frame: &mut Frame, area: Rect) {
+        let lines = match self.event.pairings.standings() {
+            Ok(standings) => standings
+                .iter()
+                .map(|standing| {
+                    Line::from(format!(
+                        "{:>3}  {}  {} ({:.3})",
+                        standing.get_rank(),
+                        standing.get_player().get_nickname(),
+                        standing.get_score(),
+                        standing.get_sos()
+                    ))
+                })
+                .collect(),
+            Err(err) => vec![Line::from(err.to_string())],
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("Standings")),
+            area,
+        );
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let pair = if self.event.can_pair() {
+            Span::raw("[p] Pair next round").bold()
+        } else {
+            Span::raw("[p] Pair next round").dark_gray()
+        };
+
+        let help = Line::from(vec![
+            Span::raw("[↑↓] Select  [Enter] Report  "),
+            pair,
+            Span::raw("  [q] Quit"),
+        ]);
+        frame.render_widget(
+            Paragraph::new(vec![help, Line::from(self.message.as_str()).italic()]),
+            area,
+        );
+    }
+
+    fn render_form(&self, frame: &mut Frame, form: &ResultForm) {
+        let [area] = Layout::horizontal([Constraint::Length(50)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [area] = Layout::vertical([Constraint::Length(form.games.len() as u16 + 6)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let pairings = self.current_pairings();
+        let pairing = &pairings[form.table - 1];
+        let player = pairing.get_player().get_nickname();
+
+        let mut lines = vec![Line::from(format!(
+            "{} vs {}",
+            player,
+            pairing
+                .get_opponent()
+                .as_ref()
+                .map(|opponent| opponent.get_nickname())
+                .unwrap_or_default()
+        ))];
+        for (idx, result) in form.games.iter().enumerate() {
+            let result = result
+                .map(|result| format!("{:?}", result))
+                .unwrap_or_else(|| "-".to_owned());
+            let line = Line::from(format!("Game {}: {} {}", idx + 1, player, result));
+            lines.push(if idx == form.game {
+                line.reversed()
+            } else {
+                line
+            });
+        }
+        lines.push(Line::from(""));
+        lines.push(if form.games.len() > 1 {
+            Line::from("[w/l/d] Game  [6] 6-0  [3] 3-3  [0] 0-6").dark_gray()
+        } else {
+            Line::from("[w/l/d] Game").dark_gray()
+        });
+        lines.push(Line::from("[Enter] Save  [Esc] Cancel").dark_gray());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(format!("Table {}", form.table))),
+            area,
+        );
+    }
+}
+
+fn run_app(terminal: &mut DefaultTerminal, app: &mut App, path: &Path) -> CliResult<()> {
+    while !app.quit {
+        terminal.draw(|frame| app.render(frame))?;
+
+        if let TermEvent::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && app.handle_key(key) {
+                app.event.save(path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs the TUI against the tournament state file until the user quits
+///
+/// The state file is saved after every change
+pub fn run(path: &Path) -> CliResult<()> {
+    let mut app = App::new(Event::load(path)?);
+
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, &mut app, path);
+    ratatui::restore();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyModifiers;
+    use ratatui::Terminal;
+
+    use netrunner_pairings::{Pairings, Player};
+
+    use super::*;
+
+    fn app(players: usize) -> App {
+        let mut event = Event::new(
+            "Test".to_owned(),
+            "2023-01-01".to_owned(),
+            Pairings::new_swiss().with_seed(0),
+        );
+        for idx in 0..players {
+            event
+                .add_player(Player::new("first", format!("last{}", idx), None))
+                .unwrap();
+        }
+        App::new(event)
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> bool {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn render(app: &mut App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        format!("{:?}", terminal.backend().buffer())
+    }
+
+    #[test]
+    fn score() {
+        let scoring = ScoringSystem::standard();
+        assert_eq!(super::score(&[Result::Win, Result::Win], &scoring), "6-0");
+        assert_eq!(super::score(&[Result::Win, Result::Loss], &scoring), "3-3");
+        assert_eq!(super::score(&[Result::Draw, Result::Loss], &scoring), "1-4");
+        assert_eq!(super::score(&[Result::Loss], &scoring), "0-3");
+    }
+
+    #[test]
+    fn report_and_pair() {
+        let mut app = app(4);
+        assert!(press(&mut app, KeyCode::Char('p')));
+        assert_eq!(app.current_pairings().len(), 2);
+
+        // pairing stays disabled until every table is reported
+        assert!(!press(&mut app, KeyCode::Char('p')));
+        assert_eq!(app.event.pairings.get_rounds().len(), 1);
+
+        assert!(!press(&mut app, KeyCode::Enter));
+        assert!(!press(&mut app, KeyCode::Char('6')));
+        assert!(press(&mut app, KeyCode::Enter));
+        assert_eq!(
+            app.event.get_results(1),
+            Some(vec![Result::Win, Result::Win])
+        );
+        assert!(!app.event.can_pair());
+
+        // enter each game on the second table
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('w'));
+        press(&mut app, KeyCode::Char('d'));
+        assert!(press(&mut app, KeyCode::Enter));
+        assert!(app.event.can_pair());
+
+        assert!(press(&mut app, KeyCode::Char('p')));
+        assert_eq!(app.event.pairings.get_rounds().len(), 2);
+    }
+
+    #[test]
+    fn incomplete_form() {
+        let mut app = app(2);
+        press(&mut app, KeyCode::Char('p'));
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('w'));
+        assert!(!press(&mut app, KeyCode::Enter));
+        assert!(app.form.is_some());
+
+        assert!(!press(&mut app, KeyCode::Esc));
+        assert!(app.form.is_none());
+        assert!(app.event.get_results(1).is_none());
+    }
+
+    #[test]
+    fn highlight_unreported() {
+        let mut app = app(4);
+        press(&mut app, KeyCode::Char('p'));
+        let screen = render(&mut app);
+        assert!(screen.contains("Pairings"));
+        assert!(screen.contains("[p] Pair next round"));
+
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('0'));
+        press(&mut app, KeyCode::Enter);
+        let screen = render(&mut app);
+        assert!(screen.contains("0-6"));
+    }
+}
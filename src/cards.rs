@@ -0,0 +1,818 @@
+//! Offline card database for checking identities and decklists
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{DeckCard, Decklist, Faction, Identity, PairingsError, Side};
+
+/// Sample card data bundled with the crate, in the NetrunnerDB API format
+const SAMPLE_CARDS: &str = include_str!("../data/netrunnerdb/cards.json");
+const SAMPLE_PACKS: &str = include_str!("../data/netrunnerdb/packs.json");
+const SAMPLE_CYCLES: &str = include_str!("../data/netrunnerdb/cycles.json");
+const SAMPLE_MWL: &str = include_str!("../data/netrunnerdb/mwl.json");
+
+/// Copies of a card allowed in a deck when the card data doesn't say otherwise
+const DEFAULT_DECK_LIMIT: u32 = 3;
+
+/// Tournament formats, deciding which cards are legal
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Standard,
+    Startup,
+    Eternal,
+}
+
+impl Format {
+    /// Every format
+    pub const ALL: [Self; 3] = [Self::Standard, Self::Startup, Self::Eternal];
+
+    /// Gets the format's name
+    #[inline]
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::Startup => "Startup",
+            Self::Eternal => "Eternal",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = PairingsError;
+
+    /// Parses a format from its name, ignoring case
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.get_name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| PairingsError::InvalidDecklist(format!("unknown format {}", s)))
+    }
+}
+
+/// Card types
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardType {
+    Identity,
+    Agenda,
+    Asset,
+    Upgrade,
+    Operation,
+    Ice,
+    Event,
+    Hardware,
+    Resource,
+    Program,
+}
+
+/// A card, combining every printing of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    title: String,
+    card_type: CardType,
+    faction: Faction,
+
+    // NetrunnerDB codes and pack codes of each printing
+    codes: Vec<String>,
+    packs: Vec<String>,
+
+    influence: u32,
+    deck_limit: u32,
+    agenda_points: Option<u32>,
+
+    // identities only
+    minimum_deck_size: Option<u32>,
+    influence_limit: Option<u32>,
+}
+
+impl Card {
+    /// Gets the card's title
+    #[inline]
+    pub fn get_title(&self) -> &String {
+        &self.title
+    }
+
+    /// Gets the card's type
+    #[inline]
+    pub fn get_type(&self) -> CardType {
+        self.card_type
+    }
+
+    /// Gets the card's faction
+    #[inline]
+    pub fn get_faction(&self) -> Faction {
+        self.faction
+    }
+
+    /// Gets the side the card is played on
+    #[inline]
+    pub fn get_side(&self) -> Side {
+        self.faction.get_side()
+    }
+
+    /// Gets the NetrunnerDB codes of every printing of the card
+    #[inline]
+    pub fn get_codes(&self) -> &Vec<String> {
+        &self.codes
+    }
+
+    /// Gets the codes of every pack the card was printed in
+    #[inline]
+    pub fn get_packs(&self) -> &Vec<String> {
+        &self.packs
+    }
+
+    /// Gets the influence cost of including the card in another faction's deck
+    #[inline]
+    pub fn get_influence(&self) -> u32 {
+        self.influence
+    }
+
+    /// Gets the number of copies allowed in a deck
+    #[inline]
+    pub fn get_deck_limit(&self) -> u32 {
+        self.deck_limit
+    }
+
+    /// Gets the agenda points of an agenda
+    #[inline]
+    pub fn get_agenda_points(&self) -> Option<u32> {
+        self.agenda_points
+    }
+
+    /// Gets the minimum deck size of an identity
+    #[inline]
+    pub fn get_minimum_deck_size(&self) -> Option<u32> {
+        self.minimum_deck_size
+    }
+
+    /// Gets the influence limit of an identity, which is None for unlimited influence
+    #[inline]
+    pub fn get_influence_limit(&self) -> Option<u32> {
+        self.influence_limit
+    }
+
+    /// Gets the halves of an identity's title, such as `Noise` and `Hacker Extraordinaire`
+    /// for `Noise: Hacker Extraordinaire`
+    fn get_short_titles(&self) -> Vec<&str> {
+        match self.title.split_once(':') {
+            Some((name, subtitle)) if self.card_type == CardType::Identity => {
+                vec![name, subtitle]
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Gets an identity corresponding to the card
+    #[inline]
+    pub fn to_identity(&self) -> Identity {
+        Identity::new(self.title.clone()).with_faction(self.faction)
+    }
+}
+
+/// A single printing in NetrunnerDB card data
+#[derive(Debug, Deserialize)]
+struct CardData {
+    #[serde(default)]
+    code: Option<String>,
+    title: String,
+    type_code: CardType,
+    faction_code: Faction,
+    pack_code: String,
+    #[serde(default)]
+    faction_cost: Option<u32>,
+    #[serde(default)]
+    deck_limit: Option<u32>,
+    #[serde(default)]
+    agenda_points: Option<u32>,
+    #[serde(default)]
+    minimum_deck_size: Option<u32>,
+    #[serde(default)]
+    influence_limit: Option<u32>,
+}
+
+/// A pack in NetrunnerDB pack data
+#[derive(Debug, Deserialize)]
+struct PackData {
+    code: String,
+    cycle_code: String,
+}
+
+/// A cycle in NetrunnerDB cycle data
+#[derive(Debug, Deserialize)]
+struct CycleData {
+    code: String,
+    #[serde(default)]
+    rotated: bool,
+}
+
+/// A ban list in NetrunnerDB MWL data
+#[derive(Debug, Deserialize)]
+struct MwlData {
+    name: String,
+    #[serde(default)]
+    active: bool,
+
+    // restrictions by card code
+    cards: HashMap<String, MwlCardData>,
+}
+
+/// A card's restriction in a ban list
+#[derive(Debug, Deserialize)]
+struct MwlCardData {
+    #[serde(default)]
+    deck_limit: Option<u32>,
+}
+
+/// NetrunnerDB API data
+#[derive(Debug, Deserialize)]
+struct ApiData<T> {
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    last_updated: Option<String>,
+    data: Vec<T>,
+}
+
+impl<T: for<'de> Deserialize<'de>> ApiData<T> {
+    fn from_json(json: &str) -> std::result::Result<Self, PairingsError> {
+        serde_json::from_str(json).map_err(|err| PairingsError::InvalidImport(err.to_string()))
+    }
+}
+
+/// Cards that are legal in a format
+#[derive(Debug, Clone)]
+struct FormatPool {
+    // None if every pack is legal
+    packs: Option<HashSet<String>>,
+
+    // card titles
+    banned: HashSet<String>,
+}
+
+/// Simplifies a card name for comparisons, ignoring case, accents and punctuation
+fn normalize(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|c| match c {
+            'à' | 'á' | 'â' | 'ä' | 'ã' | 'å' => Some('a'),
+            'è' | 'é' | 'ê' | 'ë' => Some('e'),
+            'ì' | 'í' | 'î' | 'ï' => Some('i'),
+            'ò' | 'ó' | 'ô' | 'ö' | 'õ' | 'ø' => Some('o'),
+            'ù' | 'ú' | 'û' | 'ü' => Some('u'),
+            'ñ' => Some('n'),
+            'ç' => Some('c'),
+            c if c.is_alphanumeric() => Some(c),
+            c if c.is_whitespace() || c == ':' || c == '-' || c == '/' => Some(' '),
+            _ => None,
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .join(" ")
+}
+
+/// Counts the single character edits needed to turn one string into another
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { diagonal } else { diagonal + 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Netrunner card data for looking up cards without network access
+///
+/// A local NetrunnerDB dump can be loaded with `from_netrunnerdb` before an event.
+/// The crate only bundles a small sample of cards, which isn't enough to check real decks
+#[derive(Debug, Clone)]
+pub struct CardDatabase {
+    version: String,
+    cards: Vec<Card>,
+
+    // card indices by normalized title and by code
+    titles: HashMap<String, usize>,
+    codes: HashMap<String, usize>,
+
+    formats: HashMap<Format, FormatPool>,
+    ban_list: Option<String>,
+}
+
+impl CardDatabase {
+    /// Loads the sample card data bundled with the crate
+    ///
+    /// This only has a few cards from the Core Set and System Gateway, with a sample ban list,
+    /// so it's only useful for examples and tests
+    pub fn sample() -> Self {
+        Self::from_netrunnerdb(SAMPLE_CARDS, SAMPLE_PACKS, SAMPLE_CYCLES, SAMPLE_MWL)
+            .expect("sample card data is valid")
+    }
+
+    /// Loads card data from a NetrunnerDB API card dump, without any formats
+    ///
+    /// Each printing of a card is combined into a single card. The version is taken from
+    /// the dump's `last_updated` or `version` field
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON isn't NetrunnerDB card data
+    pub fn from_json(json: &str) -> std::result::Result<Self, PairingsError> {
+        let data = ApiData::<CardData>::from_json(json)?;
+
+        let mut database = Self {
+            version: data
+                .last_updated
+                .or(data.version)
+                .unwrap_or_else(|| "unknown".to_owned()),
+            cards: vec![],
+            titles: HashMap::new(),
+            codes: HashMap::new(),
+            formats: HashMap::new(),
+            ban_list: None,
+        };
+        for printing in data.data {
+            database.add_printing(printing);
+        }
+        Ok(database)
+    }
+
+    /// Loads card data from NetrunnerDB API cards, packs, cycles and MWL dumps
+    ///
+    /// Standard has the packs of every cycle that hasn't rotated, and bans the cards the
+    /// active MWL limits to 0 copies. Eternal has every pack, and its points list isn't
+    /// checked. Startup's card pool isn't part of the data, so it isn't defined
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON isn't NetrunnerDB data, or there's no active MWL
+    pub fn from_netrunnerdb(
+        cards: &str,
+        packs: &str,
+        cycles: &str,
+        mwl: &str,
+    ) -> std::result::Result<Self, PairingsError> {
+        let mut database = Self::from_json(cards)?;
+
+        let cycles = ApiData::<CycleData>::from_json(cycles)?;
+        let rotated = cycles
+            .data
+            .iter()
+            .filter(|cycle| cycle.rotated)
+            .map(|cycle| cycle.code.as_str())
+            .collect::<HashSet<_>>();
+        let standard = ApiData::<PackData>::from_json(packs)?
+            .data
+            .into_iter()
+            .filter(|pack| !rotated.contains(pack.cycle_code.as_str()))
+            .map(|pack| pack.code)
+            .collect();
+
+        let ban_list = ApiData::<MwlData>::from_json(mwl)?
+            .data
+            .into_iter()
+            .find(|list| list.active)
+            .ok_or_else(|| PairingsError::InvalidImport("there is no active MWL".to_owned()))?;
+        let banned = ban_list
+            .cards
+            .iter()
+            .filter(|(_, card)| card.deck_limit == Some(0))
+            .filter_map(|(code, _)| database.codes.get(code))
+            .map(|idx| database.cards[*idx].title.clone())
+            .collect();
+
+        database.formats.insert(
+            Format::Standard,
+            FormatPool {
+                packs: Some(standard),
+                banned,
+            },
+        );
+        database.formats.insert(
+            Format::Eternal,
+            FormatPool {
+                packs: None,
+                banned: HashSet::new(),
+            },
+        );
+        database.ban_list = Some(ban_list.name);
+        Ok(database)
+    }
+
+    fn add_printing(&mut self, printing: CardData) {
+        let title = normalize(&printing.title);
+        let idx = *self.titles.entry(title).or_insert_with(|| {
+            self.cards.push(Card {
+                title: printing.title,
+                card_type: printing.type_code,
+                faction: printing.faction_code,
+                codes: vec![],
+                packs: vec![],
+                influence: printing.faction_cost.unwrap_or(0),
+                deck_limit: printing.deck_limit.unwrap_or(DEFAULT_DECK_LIMIT),
+                agenda_points: printing.agenda_points,
+                minimum_deck_size: printing.minimum_deck_size,
+                influence_limit: printing.influence_limit,
+            });
+            self.cards.len() - 1
+        });
+
+        let card = &mut self.cards[idx];
+        if let Some(code) = printing.code {
+            card.codes.push(code.clone());
+            self.codes.insert(code, idx);
+        }
+        if !card.packs.contains(&printing.pack_code) {
+            card.packs.push(printing.pack_code);
+        }
+    }
+
+    /// Gets the version of the card data
+    #[inline]
+    pub fn get_version(&self) -> &String {
+        &self.version
+    }
+
+    /// Gets the name of the Standard ban list, if the data has one
+    #[inline]
+    pub fn get_ban_list(&self) -> Option<&String> {
+        self.ban_list.as_ref()
+    }
+
+    /// Gets every card
+    #[inline]
+    pub fn get_cards(&self) -> &Vec<Card> {
+        &self.cards
+    }
+
+    /// Gets a card by its NetrunnerDB code or its exact title,
+    /// ignoring case, accents and punctuation
+    pub fn get_card(&self, name: impl AsRef<str>) -> Option<&Card> {
+        let name = name.as_ref().trim();
+        let idx = self
+            .codes
+            .get(name)
+            .or_else(|| self.titles.get(&normalize(name)))?;
+        Some(&self.cards[*idx])
+    }
+
+    /// Finds a card by its code or title, or by either half of an identity's title
+    ///
+    /// # Errors
+    ///
+    /// Returns an error suggesting the closest card if no card has the name
+    pub fn find_card(&self, name: impl AsRef<str>) -> std::result::Result<&Card, PairingsError> {
+        let name = name.as_ref();
+        if let Some(card) = self.get_card(name) {
+            return Ok(card);
+        }
+
+        let normalized = normalize(name);
+        let mut identities = self.cards.iter().filter(|card| {
+            card.get_short_titles()
+                .into_iter()
+                .any(|title| normalize(title) == normalized)
+        });
+        if let (Some(card), None) = (identities.next(), identities.next()) {
+            return Ok(card);
+        }
+
+        // allow roughly one typo for every four characters
+        let max_distance = (normalized.chars().count() / 4).max(2);
+        let suggestion = self
+            .cards
+            .iter()
+            .flat_map(|card| {
+                let mut titles = card.get_short_titles();
+                titles.push(&card.title);
+                titles
+                    .into_iter()
+                    .map(|title| (edit_distance(&normalized, &normalize(title)), card))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, card)| card.title.clone());
+
+        Err(PairingsError::UnknownCard {
+            name: name.trim().to_owned(),
+            suggestion,
+        })
+    }
+
+    /// Checks if the card data defines the format's card pool and ban list
+    #[inline]
+    pub fn has_format(&self, format: Format) -> bool {
+        self.formats.contains_key(&format)
+    }
+
+    /// Gets the format's card pool and ban list
+    fn get_format(&self, format: Format) -> std::result::Result<&FormatPool, PairingsError> {
+        self.formats
+            .get(&format)
+            .ok_or(PairingsError::UndefinedFormat(format))
+    }
+
+    /// Checks if any printing of a card is in one of the format's packs
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the card data doesn't define the format
+    pub fn is_in_pool(
+        &self,
+        card: &Card,
+        format: Format,
+    ) -> std::result::Result<bool, PairingsError> {
+        Ok(match &self.get_format(format)?.packs {
+            Some(packs) => card.packs.iter().any(|pack| packs.contains(pack)),
+            None => true,
+        })
+    }
+
+    /// Checks if a card is on the format's ban list
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the card data doesn't define the format
+    pub fn is_banned(
+        &self,
+        card: &Card,
+        format: Format,
+    ) -> std::result::Result<bool, PairingsError> {
+        Ok(self.get_format(format)?.banned.contains(&card.title))
+    }
+
+    /// Checks if a card is legal in a format
+    ///
+    /// A card is legal if any printing of it is in one of the format's packs,
+    /// and it isn't banned
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the card data doesn't define the format
+    #[inline]
+    pub fn is_legal(
+        &self,
+        card: &Card,
+        format: Format,
+    ) -> std::result::Result<bool, PairingsError> {
+        Ok(self.is_in_pool(card, format)? && !self.is_banned(card, format)?)
+    }
+
+    /// Looks up a registered identity, checking it's a legal identity for the side
+    ///
+    /// Returns the identity with its card's exact name and faction
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the card is unknown, isn't an identity for the side,
+    /// or isn't legal in the format, or if the card data doesn't define the format
+    pub fn check_identity(
+        &self,
+        identity: &Identity,
+        side: Side,
+        format: Format,
+    ) -> std::result::Result<Identity, PairingsError> {
+        let card = self.find_card(identity.get_name())?;
+        if card.card_type != CardType::Identity || card.get_side() != side {
+            return Err(PairingsError::InvalidDecklist(format!(
+                "{} is not a {:?} identity",
+                card.title, side
+            )));
+        }
+        if !self.is_legal(card, format)? {
+            return Err(PairingsError::IllegalCard {
+                card: card.title.clone(),
+                format,
+            });
+        }
+        Ok(card.to_identity())
+    }
+
+    /// Looks up every card in a decklist, replacing codes and misspelt names with exact titles
+    ///
+    /// JSON decklists list their identity as a card, which becomes the decklist's identity
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any card is unknown
+    pub fn resolve_decklist(
+        &self,
+        decklist: &Decklist,
+    ) -> std::result::Result<Decklist, PairingsError> {
+        let mut identity = decklist
+            .get_identity()
+            .map(|identity| self.find_card(identity).map(|card| card.title.clone()))
+            .transpose()?;
+
+        let mut cards = vec![];
+        for deck_card in decklist.get_cards() {
+            let card = self.find_card(deck_card.get_card())?;
+            if card.card_type == CardType::Identity && identity.is_none() {
+                identity = Some(card.title.clone());
+            } else {
+                cards.push(DeckCard::new(card.title.clone(), deck_card.get_count()));
+            }
+        }
+
+        let resolved = Decklist::new(identity, cards);
+        Ok(match decklist.get_name() {
+            Some(name) => resolved.with_name(name.clone()),
+            None => resolved,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        let database = CardDatabase::sample();
+        assert_eq!(database.get_version(), "2023-10-01T00:00:00+00:00");
+        assert_eq!(database.get_ban_list().unwrap(), "Sample Ban List");
+
+        let card = database.get_card("01050").unwrap();
+        assert_eq!(card.get_title(), "Sure Gamble");
+        assert_eq!(card.get_packs(), &vec!["core".to_owned(), "sg".to_owned()]);
+        assert_eq!(card.get_side(), Side::Runner);
+
+        assert_eq!(database.get_card("deja vu").unwrap().get_title(), "Déjà Vu");
+        assert_eq!(
+            database
+                .get_card("Kate Mac McCaffrey Digital Tinker")
+                .unwrap()
+                .get_minimum_deck_size(),
+            Some(45)
+        );
+        assert_eq!(Format::from_str("startup"), Ok(Format::Startup));
+    }
+
+    #[test]
+    fn typos() {
+        let database = CardDatabase::sample();
+        assert_eq!(
+            database.find_card("Noise").unwrap().get_title(),
+            "Noise: Hacker Extraordinaire"
+        );
+        assert_eq!(
+            database.find_card("Hedge Fnud"),
+            Err(PairingsError::UnknownCard {
+                name: "Hedge Fnud".to_owned(),
+                suggestion: Some("Hedge Fund".to_owned()),
+            })
+        );
+        assert_eq!(
+            database.find_card("Gabriel Santaigo"),
+            Err(PairingsError::UnknownCard {
+                name: "Gabriel Santaigo".to_owned(),
+                suggestion: Some("Gabriel Santiago: Consummate Professional".to_owned()),
+            })
+        );
+        assert!(matches!(
+            database.find_card("Something Else Entirely"),
+            Err(PairingsError::UnknownCard {
+                suggestion: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn identity_legality() {
+        let database = CardDatabase::sample();
+
+        let identity = database
+            .check_identity(
+                &Identity::new("built to last"),
+                Side::Corp,
+                Format::Standard,
+            )
+            .unwrap();
+        assert_eq!(identity.get_name(), "Weyland Consortium: Built to Last");
+        assert_eq!(identity.get_faction(), Some(Faction::WeylandConsortium));
+
+        let noise = Identity::new("Noise: Hacker Extraordinaire");
+        assert!(database
+            .check_identity(&noise, Side::Runner, Format::Eternal)
+            .is_ok());
+        assert_eq!(
+            database.check_identity(&noise, Side::Runner, Format::Standard),
+            Err(PairingsError::IllegalCard {
+                card: "Noise: Hacker Extraordinaire".to_owned(),
+                format: Format::Standard,
+            })
+        );
+        // NetrunnerDB data doesn't have the Startup card pool
+        assert_eq!(
+            database.check_identity(&noise, Side::Runner, Format::Startup),
+            Err(PairingsError::UndefinedFormat(Format::Startup))
+        );
+        assert!(matches!(
+            database.check_identity(&noise, Side::Corp, Format::Eternal),
+            Err(PairingsError::InvalidDecklist(_))
+        ));
+        assert!(matches!(
+            database.check_identity(&Identity::new("Sure Gamble"), Side::Runner, Format::Eternal),
+            Err(PairingsError::InvalidDecklist(_))
+        ));
+    }
+
+    #[test]
+    fn format_pools() {
+        let database = CardDatabase::sample();
+
+        let desperado = database.get_card("Desperado").unwrap();
+        assert_eq!(database.is_banned(desperado, Format::Standard), Ok(true));
+        assert_eq!(database.is_in_pool(desperado, Format::Eternal), Ok(true));
+        assert_eq!(database.is_legal(desperado, Format::Eternal), Ok(true));
+
+        // reprinted in System Gateway
+        let sure_gamble = database.get_card("Sure Gamble").unwrap();
+        assert_eq!(database.is_legal(sure_gamble, Format::Standard), Ok(true));
+        let noise = database.get_card("Noise: Hacker Extraordinaire").unwrap();
+        assert_eq!(database.is_in_pool(noise, Format::Standard), Ok(false));
+
+        assert_eq!(
+            database.is_legal(noise, Format::Startup),
+            Err(PairingsError::UndefinedFormat(Format::Startup))
+        );
+
+        let no_ban_list = r#"{"data": [{"name": "Old List", "active": false, "cards": {}}]}"#;
+        assert!(matches!(
+            CardDatabase::from_netrunnerdb(SAMPLE_CARDS, SAMPLE_PACKS, SAMPLE_CYCLES, no_ban_list),
+            Err(PairingsError::InvalidImport(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_decklist() {
+        let database = CardDatabase::sample();
+
+        let json = r#"{"name": "Noisy", "cards": {"01001": 1, "01050": 3, "01007": 2}}"#;
+        let decklist = database
+            .resolve_decklist(&Decklist::from_json(json).unwrap())
+            .unwrap();
+        assert_eq!(decklist.get_name().unwrap(), "Noisy");
+        assert_eq!(
+            decklist.get_identity().unwrap(),
+            "Noise: Hacker Extraordinaire"
+        );
+        assert_eq!(decklist.get_card_count(), 5);
+
+        let decklist = Decklist::new(None, vec![DeckCard::new("Sure Gamlbe", 3)]);
+        assert!(matches!(
+            database.resolve_decklist(&decklist),
+            Err(PairingsError::UnknownCard { .. })
+        ));
+    }
+
+    #[test]
+    fn from_dump() {
+        let json = r#"{
+            "last_updated": "2023-11-01T00:00:00+00:00",
+            "data": [{
+                "code": "99001",
+                "title": "Test Identity: Tester",
+                "type_code": "identity",
+                "faction_code": "apex",
+                "pack_code": "sg",
+                "faction_cost": null,
+                "influence_limit": null,
+                "minimum_deck_size": 45
+            }]
+        }"#;
+        let database = CardDatabase::from_json(json).unwrap();
+        assert_eq!(database.get_version(), "2023-11-01T00:00:00+00:00");
+
+        let card = database.get_card("99001").unwrap();
+        assert_eq!(card.get_influence_limit(), None);
+
+        // without pack, cycle and ban list data, no format can be checked
+        assert!(!database.has_format(Format::Standard));
+        assert_eq!(
+            database.is_legal(card, Format::Standard),
+            Err(PairingsError::UndefinedFormat(Format::Standard))
+        );
+
+        assert!(CardDatabase::from_json("{}").is_err());
+    }
+}
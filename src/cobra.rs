@@ -0,0 +1,230 @@
+//! Cobra (https://cobr.ai) tournament import and export
+//!
+//! Cobra exports tournaments in the NRTM result file format used by most
+//! Netrunner results sites, adding the side balance of each player and the side
+//! played in single-sided Swiss, and marking intentional draws and 2-for-1 tables.
+//! Those are optional fields of the NRTM types, so this is a thin wrapper
+//! around an NRTM tournament.
+
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Serialize};
+
+use crate::nrtm::NrtmTournament;
+use crate::{Pairings, PairingsError};
+
+/// A Cobra tournament export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CobraTournament(NrtmTournament);
+
+impl CobraTournament {
+    /// Parses a Cobra JSON export
+    #[inline]
+    pub fn from_json(json: impl AsRef<str>) -> serde_json::Result<Self> {
+        serde_json::from_str(json.as_ref())
+    }
+
+    /// Writes the tournament as Cobra JSON
+    #[inline]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Exports the Swiss rounds and top cut of a tournament
+    ///
+    /// Players imported from Cobra keep their ids,
+    /// and other players are numbered in registration order
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than 2 players are registered
+    #[inline]
+    pub fn from_pairings(
+        pairings: &Pairings,
+        name: impl Into<String>,
+        date: impl Into<String>,
+    ) -> std::result::Result<Self, PairingsError> {
+        NrtmTournament::from_pairings(pairings, name, date).map(Self)
+    }
+
+    /// Imports the Swiss rounds and top cut of the tournament
+    ///
+    /// The tournament is single-sided if any Swiss game has a role.
+    /// Player ids are the Cobra player ids.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the players or rounds are inconsistent,
+    /// if an unreported round is followed by other rounds,
+    /// or if the elimination rounds don't follow the bracket
+    #[inline]
+    pub fn to_pairings(&self) -> std::result::Result<Pairings, PairingsError> {
+        self.0.to_pairings()
+    }
+
+    /// Gets the NRTM result file for the tournament
+    #[inline]
+    pub fn into_nrtm(self) -> NrtmTournament {
+        self.0
+    }
+}
+
+impl From<NrtmTournament> for CobraTournament {
+    #[inline]
+    fn from(tournament: NrtmTournament) -> Self {
+        Self(tournament)
+    }
+}
+
+impl Deref for CobraTournament {
+    type Target = NrtmTournament;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for CobraTournament {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nrtm::format_tiebreaker;
+    use crate::{Result, Side};
+
+    const DOUBLE_SIDED: &str = include_str!("../testdata/cobra/double-sided.json");
+    const SINGLE_SIDED: &str = include_str!("../testdata/cobra/single-sided.json");
+
+    fn assert_standings(tournament: &CobraTournament, pairings: &Pairings) {
+        let standings = pairings.standings().unwrap();
+        assert_eq!(standings.len(), tournament.players.len());
+
+        for (player, standing) in tournament.players.iter().zip(standings) {
+            assert_eq!(*standing.get_player().get_id(), player.id.to_string());
+            assert_eq!(standing.get_player().get_nickname(), player.name);
+            assert_eq!(standing.get_rank(), player.rank);
+            assert_eq!(standing.get_score(), player.match_points);
+            assert_eq!(
+                format_tiebreaker(standing.get_sos()),
+                player.strength_of_schedule
+            );
+            assert_eq!(
+                format_tiebreaker(standing.get_esos()),
+                player.extended_strength_of_schedule
+            );
+        }
+    }
+
+    fn assert_round_trip(tournament: &CobraTournament, pairings: &Pairings) {
+        let exported =
+            CobraTournament::from_pairings(pairings, &tournament.name, &tournament.date).unwrap();
+        let exported = CobraTournament::from_json(exported.to_json().unwrap()).unwrap();
+
+        assert_eq!(exported.cut_to_top, tournament.cut_to_top);
+        assert_eq!(exported.preliminary_rounds, tournament.preliminary_rounds);
+        assert_eq!(exported.players, tournament.players);
+        assert_eq!(exported.rounds, tournament.rounds);
+    }
+
+    #[test]
+    fn double_sided() {
+        let tournament = CobraTournament::from_json(DOUBLE_SIDED).unwrap();
+        let pairings = tournament.to_pairings().unwrap();
+        assert!(!pairings.is_single_sided());
+        assert_eq!(pairings.get_rounds().len(), 2);
+        assert_eq!(pairings.players_with_byes().len(), 2);
+
+        assert_standings(&tournament, &pairings);
+        assert_round_trip(&tournament, &pairings);
+    }
+
+    #[test]
+    fn single_sided() {
+        let tournament = CobraTournament::from_json(SINGLE_SIDED).unwrap();
+        let pairings = tournament.to_pairings().unwrap();
+        assert!(pairings.is_single_sided());
+        assert_eq!(pairings.get_rounds().len(), 2);
+
+        for round in pairings.get_rounds() {
+            for pairing in round.get_pairings() {
+                assert_eq!(
+                    pairing.get_player_side(pairing.get_player()),
+                    Some(Side::Corp)
+                );
+            }
+        }
+
+        assert_standings(&tournament, &pairings);
+        assert_round_trip(&tournament, &pairings);
+    }
+
+    #[test]
+    fn intentional_draw() {
+        let mut tournament = CobraTournament::from_json(DOUBLE_SIDED).unwrap();
+        let game = &mut tournament.rounds[0][0];
+        game.intentional_draw = true;
+        for player in [&mut game.player1, &mut game.player2] {
+            player.corp_score = Some(1);
+            player.runner_score = Some(1);
+            player.combined_score = Some(2);
+        }
+
+        let pairings = tournament.to_pairings().unwrap();
+        assert_eq!(
+            pairings.get_rounds()[0].get_pairings()[0].get_results(),
+            vec![Result::IntentionalDraw; 2]
+        );
+
+        let exported = CobraTournament::from_pairings(&pairings, "ID", "2023-01-01").unwrap();
+        assert!(exported.rounds[0][0].intentional_draw);
+    }
+
+    #[test]
+    fn unreported_round() {
+        let mut tournament = CobraTournament::from_json(DOUBLE_SIDED).unwrap();
+        for game in tournament.rounds.last_mut().unwrap() {
+            game.player1.corp_score = None;
+            game.player1.runner_score = None;
+            game.player1.combined_score = None;
+            game.player2.corp_score = None;
+            game.player2.runner_score = None;
+            game.player2.combined_score = None;
+        }
+
+        let mut pairings = tournament.to_pairings().unwrap();
+        assert!(!pairings.get_rounds().last().unwrap().has_ended());
+        assert_eq!(pairings.next_round(), Err(PairingsError::RoundInProgress));
+
+        // earlier rounds need to be reported
+        let first_round = tournament.rounds[0].clone();
+        tournament.rounds.push(first_round);
+        assert_eq!(
+            tournament.to_pairings().unwrap_err(),
+            PairingsError::RoundInProgress
+        );
+    }
+
+    #[test]
+    fn invalid_import() {
+        let mut tournament = CobraTournament::from_json(DOUBLE_SIDED).unwrap();
+        tournament.rounds[0][0].player1.corp_score = Some(2);
+        assert_eq!(
+            tournament.to_pairings().unwrap_err(),
+            PairingsError::InvalidImport("unexpected game score 2".to_owned())
+        );
+
+        let mut tournament = CobraTournament::from_json(DOUBLE_SIDED).unwrap();
+        tournament.rounds[0][0].player1.id = Some(100);
+        assert_eq!(
+            tournament.to_pairings().unwrap_err(),
+            PairingsError::UnknownPlayer("100".to_owned())
+        );
+    }
+}
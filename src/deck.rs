@@ -0,0 +1,447 @@
+//! Identities and decklists registered by players
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{PairingsError, Side};
+
+/// Summary lines at the end of NetrunnerDB text exports that look like cards
+const TEXT_SUMMARY_LINES: &[&str] = &["cards", "influence spent", "agenda points"];
+
+/// Netrunner factions, including the mini-factions and neutral cards
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Faction {
+    HaasBioroid,
+    Jinteki,
+    Nbn,
+    WeylandConsortium,
+    NeutralCorp,
+    Anarch,
+    Criminal,
+    Shaper,
+    Adam,
+    Apex,
+    SunnyLebeau,
+    NeutralRunner,
+}
+
+impl Faction {
+    /// Every faction, Corp factions first
+    pub const ALL: [Self; 12] = [
+        Self::HaasBioroid,
+        Self::Jinteki,
+        Self::Nbn,
+        Self::WeylandConsortium,
+        Self::NeutralCorp,
+        Self::Anarch,
+        Self::Criminal,
+        Self::Shaper,
+        Self::Adam,
+        Self::Apex,
+        Self::SunnyLebeau,
+        Self::NeutralRunner,
+    ];
+
+    /// Gets the faction's NetrunnerDB code, such as `haas-bioroid`
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            Self::HaasBioroid => "haas-bioroid",
+            Self::Jinteki => "jinteki",
+            Self::Nbn => "nbn",
+            Self::WeylandConsortium => "weyland-consortium",
+            Self::NeutralCorp => "neutral-corp",
+            Self::Anarch => "anarch",
+            Self::Criminal => "criminal",
+            Self::Shaper => "shaper",
+            Self::Adam => "adam",
+            Self::Apex => "apex",
+            Self::SunnyLebeau => "sunny-lebeau",
+            Self::NeutralRunner => "neutral-runner",
+        }
+    }
+
+    /// Gets the faction's display name
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::HaasBioroid => "Haas-Bioroid",
+            Self::Jinteki => "Jinteki",
+            Self::Nbn => "NBN",
+            Self::WeylandConsortium => "Weyland Consortium",
+            Self::NeutralCorp | Self::NeutralRunner => "Neutral",
+            Self::Anarch => "Anarch",
+            Self::Criminal => "Criminal",
+            Self::Shaper => "Shaper",
+            Self::Adam => "Adam",
+            Self::Apex => "Apex",
+            Self::SunnyLebeau => "Sunny Lebeau",
+        }
+    }
+
+    /// Gets the side the faction's cards are played on
+    pub fn get_side(&self) -> Side {
+        match self {
+            Self::HaasBioroid
+            | Self::Jinteki
+            | Self::Nbn
+            | Self::WeylandConsortium
+            | Self::NeutralCorp => Side::Corp,
+            _ => Side::Runner,
+        }
+    }
+}
+
+impl fmt::Display for Faction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_name())
+    }
+}
+
+impl FromStr for Faction {
+    type Err = PairingsError;
+
+    /// Parses a faction from its NetrunnerDB code or its name, ignoring case
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        Self::ALL
+            .into_iter()
+            .find(|faction| {
+                faction.get_code().eq_ignore_ascii_case(s)
+                    || (faction.get_name() != "Neutral"
+                        && faction.get_name().eq_ignore_ascii_case(s))
+            })
+            .ok_or_else(|| PairingsError::InvalidDecklist(format!("unknown faction {}", s)))
+    }
+}
+
+/// A Corp or Runner identity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Identity {
+    name: String,
+
+    // None until the identity has been looked up
+    faction: Option<Faction>,
+}
+
+impl Identity {
+    /// Creates an identity from its card name
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            faction: None,
+        }
+    }
+
+    /// Sets the identity's faction
+    #[inline]
+    pub fn with_faction(mut self, faction: Faction) -> Self {
+        self.faction = Some(faction);
+        self
+    }
+
+    /// Gets the identity's card name
+    #[inline]
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    /// Gets the identity's faction, if known
+    #[inline]
+    pub fn get_faction(&self) -> Option<Faction> {
+        self.faction
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A number of copies of a card in a decklist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckCard {
+    card: String,
+    count: u32,
+}
+
+impl DeckCard {
+    /// Creates a new decklist entry
+    #[inline]
+    pub fn new(card: impl Into<String>, count: u32) -> Self {
+        Self {
+            card: card.into(),
+            count,
+        }
+    }
+
+    /// Gets the card's name, or its NetrunnerDB code for decklists imported from JSON
+    #[inline]
+    pub fn get_card(&self) -> &String {
+        &self.card
+    }
+
+    /// Gets the number of copies of the card
+    #[inline]
+    pub fn get_count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// A registered decklist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decklist {
+    name: Option<String>,
+    identity: Option<String>,
+
+    // every card in the order they were listed,
+    // which only includes the identity for JSON decklists
+    cards: Vec<DeckCard>,
+}
+
+/// Removes a trailing ` (Set Name)` from a card name
+fn strip_set_name(name: &str) -> &str {
+    match name.rfind(" (") {
+        Some(idx) if name.ends_with(')') => name[..idx].trim_end(),
+        _ => name,
+    }
+}
+
+/// Parses a decklist line such as `3x Hedge Fund (System Gateway) ●●`
+///
+/// Returns None if the line doesn't start with a card count
+fn parse_card_line(line: &str) -> Option<(u32, &str)> {
+    let digits = line.find(|c: char| !c.is_ascii_digit())?;
+    let count = line[..digits].parse().ok()?;
+
+    let rest = line[digits..].strip_prefix('x').unwrap_or(&line[digits..]);
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let name = rest.trim_end_matches(['•', '●', '○', ' ']).trim();
+    Some((count, strip_set_name(name)))
+}
+
+/// Checks if the line is a section header such as `Event (12)`
+fn is_section_header(line: &str) -> bool {
+    line.strip_suffix(')')
+        .and_then(|line| line.rsplit_once(" ("))
+        .is_some_and(|(_, count)| !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()))
+}
+
+impl Decklist {
+    /// Creates a new decklist
+    #[inline]
+    pub fn new(identity: Option<String>, cards: Vec<DeckCard>) -> Self {
+        Self {
+            name: None,
+            identity,
+            cards,
+        }
+    }
+
+    /// Sets the deck's name
+    #[inline]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Parses a NetrunnerDB plain text decklist
+    ///
+    /// The deck name and identity are the lines before the first card or section header,
+    /// and every card is a line starting with its count, such as `3x Sure Gamble`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the decklist has no cards
+    pub fn from_text(text: &str) -> std::result::Result<Self, PairingsError> {
+        let mut preamble = vec![];
+        let mut cards = vec![];
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some((count, name)) = parse_card_line(line) {
+                let summary = TEXT_SUMMARY_LINES
+                    .iter()
+                    .any(|summary| name.to_lowercase().starts_with(summary));
+                if !summary {
+                    cards.push(DeckCard::new(name, count));
+                }
+            } else if !is_section_header(line) && cards.is_empty() {
+                preamble.push(line);
+            }
+        }
+
+        if cards.is_empty() {
+            return Err(PairingsError::InvalidDecklist(
+                "the decklist has no cards".to_owned(),
+            ));
+        }
+
+        // a lone line is the identity, otherwise the deck name comes first
+        let identity = preamble.last().map(|line| strip_set_name(line).to_owned());
+        let name = (preamble.len() > 1).then(|| preamble[0].to_owned());
+        Ok(Self {
+            name,
+            identity,
+            cards,
+        })
+    }
+
+    /// Parses a NetrunnerDB JSON decklist, either a deck or an API response containing one
+    ///
+    /// Cards are given by their NetrunnerDB codes, including the identity,
+    /// since the JSON format doesn't name them
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON isn't a NetrunnerDB decklist
+    pub fn from_json(json: &str) -> std::result::Result<Self, PairingsError> {
+        let invalid = |err: &str| PairingsError::InvalidDecklist(err.to_owned());
+
+        let value: Value = serde_json::from_str(json).map_err(|err| invalid(&err.to_string()))?;
+        let deck = match value.get("data") {
+            Some(Value::Array(decks)) => decks.first().ok_or_else(|| invalid("no decklist"))?,
+            _ => &value,
+        };
+
+        let cards = deck
+            .get("cards")
+            .and_then(Value::as_object)
+            .ok_or_else(|| invalid("the decklist has no cards"))?
+            .iter()
+            .map(|(code, count)| {
+                let count = count
+                    .as_u64()
+                    .and_then(|count| u32::try_from(count).ok())
+                    .ok_or_else(|| invalid(&format!("invalid count for card {}", code)))?;
+                Ok(DeckCard::new(code.clone(), count))
+            })
+            .collect::<std::result::Result<Vec<_>, PairingsError>>()?;
+
+        Ok(Self {
+            name: deck.get("name").and_then(Value::as_str).map(str::to_owned),
+            identity: None,
+            cards,
+        })
+    }
+
+    /// Gets the deck's name
+    #[inline]
+    pub fn get_name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    /// Gets the identity named in the decklist
+    #[inline]
+    pub fn get_identity(&self) -> Option<&String> {
+        self.identity.as_ref()
+    }
+
+    /// Gets every card in the deck
+    #[inline]
+    pub fn get_cards(&self) -> &Vec<DeckCard> {
+        &self.cards
+    }
+
+    /// Gets the total number of cards in the deck
+    #[inline]
+    pub fn get_card_count(&self) -> u32 {
+        self.cards.iter().map(DeckCard::get_count).sum()
+    }
+}
+
+/// Decklists registered by a single player
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct PlayerDecklists {
+    corp: Option<Decklist>,
+    runner: Option<Decklist>,
+}
+
+impl PlayerDecklists {
+    #[inline]
+    pub(crate) fn get(&self, side: Side) -> Option<&Decklist> {
+        match side {
+            Side::Corp => self.corp.as_ref(),
+            Side::Runner => self.runner.as_ref(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn set(&mut self, side: Side, decklist: Decklist) {
+        match side {
+            Side::Corp => self.corp = Some(decklist),
+            Side::Runner => self.runner = Some(decklist),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "\
+Lose Yourself
+
+Hoshiko Shiro: Untold Protagonist (Midnight Sun)
+
+Event (9)
+3x Sure Gamble (System Gateway)
+3x Dirty Laundry (System Update 2021) ●●
+3x Creative Commission (Midnight Sun)
+
+Hardware (3)
+3x Docklands Pass (Midnight Sun)
+
+2 Paperclip
+
+15 influence spent (max 15, available 0)
+14 cards (min 40)
+Cards up to Midnight Sun
+";
+
+    #[test]
+    fn faction() {
+        assert_eq!("haas-bioroid".parse::<Faction>(), Ok(Faction::HaasBioroid));
+        assert_eq!(
+            "Weyland Consortium".parse::<Faction>(),
+            Ok(Faction::WeylandConsortium)
+        );
+        assert_eq!("NBN".parse::<Faction>(), Ok(Faction::Nbn));
+        assert!("neutral".parse::<Faction>().is_err());
+        assert_eq!(Faction::Apex.get_side(), Side::Runner);
+        assert_eq!(Faction::NeutralCorp.get_side(), Side::Corp);
+    }
+
+    #[test]
+    fn from_text() {
+        let decklist = Decklist::from_text(TEXT).unwrap();
+        assert_eq!(decklist.get_name().unwrap(), "Lose Yourself");
+        assert_eq!(
+            decklist.get_identity().unwrap(),
+            "Hoshiko Shiro: Untold Protagonist"
+        );
+        assert_eq!(decklist.get_cards().len(), 5);
+        assert_eq!(decklist.get_cards()[1], DeckCard::new("Dirty Laundry", 3));
+        assert_eq!(decklist.get_cards()[4], DeckCard::new("Paperclip", 2));
+        assert_eq!(decklist.get_card_count(), 14);
+
+        assert!(Decklist::from_text("Just a name").is_err());
+    }
+
+    #[test]
+    fn from_json() {
+        let json = r#"{"data": [{"name": "Lose Yourself", "cards": {"33001": 1, "30001": 3}}]}"#;
+        let decklist = Decklist::from_json(json).unwrap();
+        assert_eq!(decklist.get_name().unwrap(), "Lose Yourself");
+        assert_eq!(decklist.get_identity(), None);
+        assert_eq!(decklist.get_card_count(), 4);
+
+        assert!(Decklist::from_json(r#"{"name": "No cards"}"#).is_err());
+        assert!(Decklist::from_json(r#"{"cards": {"30001": -1}}"#).is_err());
+    }
+}
@@ -0,0 +1,1240 @@
+//! Elimination top cut
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    validate_results, Pairing, PairingsError, Player, Result, ResultsError, Round, RoundReports,
+    Side,
+};
+
+/// Number of games played in each elimination match
+const ELIMINATION_GAMES_PER_ROUND: usize = 1;
+
+/// Gets the bracket position of each seed for a bracket of the given size
+///
+/// Seeds are 0-based and adjacent positions play each other in the first round,
+/// so that the top seeds can only meet in the later rounds (1v8, 4v5, 2v7, 3v6)
+fn bracket_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let round_size = order.len() * 2;
+        order = order
+            .into_iter()
+            .flat_map(|seed| [seed, round_size - 1 - seed])
+            .collect();
+    }
+    order
+}
+
+/// Where a player in a double-elimination game comes from
+#[derive(Debug, Copy, Clone)]
+enum Slot {
+    /// 0-based seed
+    Seed(usize),
+
+    /// Winner of the given 0-based bracket game
+    Winner(usize),
+
+    /// Loser of the given 0-based bracket game
+    Loser(usize),
+}
+
+/// A game in a double-elimination bracket
+#[derive(Debug)]
+struct BracketGame {
+    /// 1-based elimination round the game is played in
+    round: usize,
+
+    player: Slot,
+    opponent: Slot,
+
+    /// Places shared by the loser if they are eliminated
+    eliminated: Option<(usize, usize)>,
+}
+
+impl BracketGame {
+    const fn new(round: usize, player: Slot, opponent: Slot) -> Self {
+        Self {
+            round,
+            player,
+            opponent,
+            eliminated: None,
+        }
+    }
+
+    const fn eliminates(
+        round: usize,
+        player: Slot,
+        opponent: Slot,
+        places: (usize, usize),
+    ) -> Self {
+        Self {
+            round,
+            player,
+            opponent,
+            eliminated: Some(places),
+        }
+    }
+}
+
+use Slot::{Loser as L, Seed as S, Winner as W};
+
+/// NSG 4 player double-elimination bracket
+///
+/// The last game is the grand final
+const DOUBLE_ELIMINATION_4: &[BracketGame] = &[
+    BracketGame::new(1, S(0), S(3)),
+    BracketGame::new(1, S(1), S(2)),
+    BracketGame::new(2, W(0), W(1)),
+    BracketGame::eliminates(2, L(0), L(1), (4, 4)),
+    BracketGame::eliminates(3, L(2), W(3), (3, 3)),
+    BracketGame::new(4, W(2), W(4)),
+];
+
+/// NSG 8 player double-elimination bracket
+///
+/// The last game is the grand final
+const DOUBLE_ELIMINATION_8: &[BracketGame] = &[
+    BracketGame::new(1, S(0), S(7)),
+    BracketGame::new(1, S(3), S(4)),
+    BracketGame::new(1, S(1), S(6)),
+    BracketGame::new(1, S(2), S(5)),
+    BracketGame::new(2, W(0), W(1)),
+    BracketGame::new(2, W(2), W(3)),
+    BracketGame::eliminates(2, L(0), L(1), (7, 8)),
+    BracketGame::eliminates(2, L(2), L(3), (7, 8)),
+    BracketGame::new(3, W(4), W(5)),
+    // losers from the winners bracket cross over to avoid rematches
+    BracketGame::eliminates(3, L(5), W(6), (5, 6)),
+    BracketGame::eliminates(3, L(4), W(7), (5, 6)),
+    BracketGame::eliminates(4, W(9), W(10), (4, 4)),
+    BracketGame::eliminates(5, L(8), W(11), (3, 3)),
+    BracketGame::new(6, W(8), W(12)),
+];
+
+/// NSG 16 player double-elimination bracket
+///
+/// The last game is the grand final
+const DOUBLE_ELIMINATION_16: &[BracketGame] = &[
+    BracketGame::new(1, S(0), S(15)),
+    BracketGame::new(1, S(7), S(8)),
+    BracketGame::new(1, S(3), S(12)),
+    BracketGame::new(1, S(4), S(11)),
+    BracketGame::new(1, S(1), S(14)),
+    BracketGame::new(1, S(6), S(9)),
+    BracketGame::new(1, S(2), S(13)),
+    BracketGame::new(1, S(5), S(10)),
+    BracketGame::new(2, W(0), W(1)),
+    BracketGame::new(2, W(2), W(3)),
+    BracketGame::new(2, W(4), W(5)),
+    BracketGame::new(2, W(6), W(7)),
+    BracketGame::eliminates(2, L(0), L(1), (13, 16)),
+    BracketGame::eliminates(2, L(2), L(3), (13, 16)),
+    BracketGame::eliminates(2, L(4), L(5), (13, 16)),
+    BracketGame::eliminates(2, L(6), L(7), (13, 16)),
+    BracketGame::new(3, W(8), W(9)),
+    BracketGame::new(3, W(10), W(11)),
+    // losers from the winners bracket cross over to avoid rematches
+    BracketGame::eliminates(3, L(11), W(12), (9, 12)),
+    BracketGame::eliminates(3, L(10), W(13), (9, 12)),
+    BracketGame::eliminates(3, L(9), W(14), (9, 12)),
+    BracketGame::eliminates(3, L(8), W(15), (9, 12)),
+    BracketGame::eliminates(4, W(18), W(19), (7, 8)),
+    BracketGame::eliminates(4, W(20), W(21), (7, 8)),
+    BracketGame::new(5, W(16), W(17)),
+    BracketGame::eliminates(5, L(17), W(22), (5, 6)),
+    BracketGame::eliminates(5, L(16), W(23), (5, 6)),
+    BracketGame::eliminates(6, W(25), W(26), (4, 4)),
+    BracketGame::eliminates(7, L(24), W(27), (3, 3)),
+    BracketGame::new(8, W(24), W(28)),
+];
+
+/// Gets the double-elimination bracket for the given cut size
+fn double_bracket(size: usize) -> Option<&'static [BracketGame]> {
+    match size {
+        4 => Some(DOUBLE_ELIMINATION_4),
+        8 => Some(DOUBLE_ELIMINATION_8),
+        16 => Some(DOUBLE_ELIMINATION_16),
+        _ => None,
+    }
+}
+
+/// Top cut formats
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EliminationFormat {
+    /// Single-elimination, the higher seed chooses their side
+    #[default]
+    Single,
+
+    /// NSG double-elimination for 4, 8, or 16 players
+    ///
+    /// Players play the side they've played less in the cut,
+    /// and the higher seed chooses if that doesn't decide it
+    Double,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+enum EliminationAlgorithm {
+    /// Single-elimination, tracking the ids of the players still in the bracket in bracket order
+    Single(Vec<String>),
+
+    /// Double-elimination, tracking the (winner, loser) ids of each bracket game played so far
+    ///
+    /// A grand final rematch follows the bracket games
+    Double(Vec<(String, String)>),
+}
+
+impl EliminationAlgorithm {
+    /// Creates a new single-elimination bracket for the given seeds
+    #[inline]
+    fn new_single(seeds: &[Player]) -> Self {
+        Self::Single(
+            bracket_order(seeds.len())
+                .into_iter()
+                .map(|seed| seeds[seed].get_id().clone())
+                .collect(),
+        )
+    }
+
+    /// Creates a new double-elimination bracket
+    #[inline]
+    fn new_double() -> Self {
+        Self::Double(vec![])
+    }
+
+    #[inline]
+    fn get_total_rounds(&self, player_count: usize) -> usize {
+        match self {
+            Self::Single(_) => player_count.trailing_zeros() as usize,
+            Self::Double(_) => double_bracket(player_count)
+                .and_then(|bracket| bracket.last())
+                .map(|game| game.round)
+                .unwrap_or(0),
+        }
+    }
+
+    /// Gets the id of the player in the given double-elimination bracket slot
+    fn get_slot<'a>(games: &'a [(String, String)], seeds: &'a [Player], slot: Slot) -> &'a String {
+        match slot {
+            Slot::Seed(seed) => seeds[seed].get_id(),
+            Slot::Winner(game) => &games[game].0,
+            Slot::Loser(game) => &games[game].1,
+        }
+    }
+
+    /// Checks if the losers bracket player won the double-elimination grand final,
+    /// which means they play again
+    fn needs_rematch(games: &[(String, String)], seeds: &[Player]) -> bool {
+        let bracket = double_bracket(seeds.len()).unwrap();
+        if games.len() != bracket.len() {
+            return false;
+        }
+
+        let grand_final = bracket.last().unwrap();
+        games.last().unwrap().0 == *Self::get_slot(games, seeds, grand_final.opponent)
+    }
+
+    /// Gets the ids of the players in each match of the next round
+    ///
+    /// Returns no matches once the top cut is finished
+    fn next_pairings<'a>(&'a self, seeds: &'a [Player]) -> Vec<(&'a String, &'a String)> {
+        match self {
+            Self::Single(remaining) => {
+                if remaining.len() < 2 {
+                    return vec![];
+                }
+
+                remaining
+                    .chunks_exact(2)
+                    .map(|players| (&players[0], &players[1]))
+                    .collect()
+            }
+            Self::Double(games) => {
+                let bracket = double_bracket(seeds.len()).unwrap();
+                match bracket.get(games.len()) {
+                    Some(next) => bracket[games.len()..]
+                        .iter()
+                        .take_while(|game| game.round == next.round)
+                        .map(|game| {
+                            (
+                                Self::get_slot(games, seeds, game.player),
+                                Self::get_slot(games, seeds, game.opponent),
+                            )
+                        })
+                        .collect(),
+                    None if Self::needs_rematch(games, seeds) => {
+                        let (winner, loser) = games.last().unwrap();
+                        vec![(winner, loser)]
+                    }
+                    None => vec![],
+                }
+            }
+        }
+    }
+
+    /// Gets the (first place, last place, id) of every player who has finished the top cut
+    fn get_placements<'a>(
+        &'a self,
+        seeds: &'a [Player],
+        rounds: &'a [Round],
+    ) -> Vec<(usize, usize, &'a String)> {
+        let mut placements = vec![];
+        match self {
+            Self::Single(remaining) => {
+                let mut remaining_count = seeds.len();
+                for round in rounds.iter().filter(|round| round.has_ended()) {
+                    let eliminated = remaining_count / 2;
+                    // a loaded or imported round might not have a winner for every table
+                    for pairing in round.get_pairings() {
+                        let Some(loser) = pairing
+                            .get_winner()
+                            .and_then(|winner| pairing.get_player_opponent(winner))
+                        else {
+                            continue;
+                        };
+                        placements.push((eliminated + 1, remaining_count, loser.get_id()));
+                    }
+                    remaining_count = eliminated;
+                }
+
+                if let [champion] = remaining.as_slice() {
+                    placements.push((1, 1, champion));
+                }
+            }
+            Self::Double(games) => {
+                let bracket = double_bracket(seeds.len()).unwrap();
+                for (game, (_, loser)) in bracket.iter().zip(games) {
+                    if let Some((first, last)) = game.eliminated {
+                        placements.push((first, last, loser));
+                    }
+                }
+
+                if games.len() >= bracket.len() && !Self::needs_rematch(games, seeds) {
+                    let (winner, loser) = games.last().unwrap();
+                    placements.push((2, 2, loser));
+                    placements.push((1, 1, winner));
+                }
+            }
+        }
+
+        placements
+    }
+
+    fn round_ended(&mut self, round: &Round) {
+        let winners = round.get_pairings().iter().filter_map(|pairing| {
+            let winner = pairing.get_winner()?;
+            let loser = pairing.get_player_opponent(winner)?;
+            Some((winner.get_id().clone(), loser.get_id().clone()))
+        });
+
+        match self {
+            Self::Single(remaining) => {
+                *remaining = winners.map(|(winner, _)| winner).collect();
+            }
+            Self::Double(games) => games.extend(winners),
+        }
+    }
+}
+
+/// A player's final place in the top cut
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    place: usize,
+    last_place: usize,
+    player: Player,
+}
+
+impl Placement {
+    /// Gets the highest place shared by the player
+    #[inline]
+    pub fn get_place(&self) -> usize {
+        self.place
+    }
+
+    /// Gets the lowest place shared by the player
+    #[inline]
+    pub fn get_last_place(&self) -> usize {
+        self.last_place
+    }
+
+    /// Checks if the place is shared with other players, such as 5-6th
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        self.place != self.last_place
+    }
+
+    /// Gets the player
+    #[inline]
+    pub fn get_player(&self) -> &Player {
+        &self.player
+    }
+}
+
+impl fmt::Display for Placement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match (self.last_place % 10, self.last_place % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+
+        if self.is_shared() {
+            write!(f, "{}-{}{}", self.place, self.last_place, suffix)
+        } else {
+            write!(f, "{}{}", self.place, suffix)
+        }
+    }
+}
+
+/// Elimination top cut
+///
+/// Each match is a single game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Elimination {
+    algorithm: EliminationAlgorithm,
+
+    // players in seed order, highest seed first
+    seeds: Vec<Player>,
+
+    rounds: Vec<Round>,
+}
+
+impl Elimination {
+    /// Ensures the seeds can fill a bracket
+    fn validate_seeds(seeds: &[Player]) -> std::result::Result<(), PairingsError> {
+        if seeds.len() < 2 {
+            return Err(PairingsError::NotEnoughPlayers(seeds.len()));
+        }
+
+        if !seeds.len().is_power_of_two() {
+            return Err(PairingsError::InvalidCutSize(seeds.len()));
+        }
+
+        for (idx, player) in seeds.iter().enumerate() {
+            if seeds[..idx].contains(player) {
+                return Err(PairingsError::DuplicatePlayer(player.get_id().clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates a new top cut in the given format
+    ///
+    /// Seeds are the top cut players, highest seed first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of seeds isn't supported by the format,
+    /// or if a player is seeded more than once
+    pub fn new(
+        seeds: Vec<Player>,
+        format: EliminationFormat,
+    ) -> std::result::Result<Self, PairingsError> {
+        match format {
+            EliminationFormat::Single => Self::new_single(seeds),
+            EliminationFormat::Double => Self::new_double(seeds),
+        }
+    }
+
+    /// Creates a new single-elimination bracket
+    ///
+    /// Seeds are the top cut players, highest seed first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of seeds is not a power of 2,
+    /// or if a player is seeded more than once
+    pub fn new_single(seeds: Vec<Player>) -> std::result::Result<Self, PairingsError> {
+        Self::validate_seeds(&seeds)?;
+
+        Ok(Self {
+            algorithm: EliminationAlgorithm::new_single(&seeds),
+            seeds,
+            rounds: vec![],
+        })
+    }
+
+    /// Creates a new double-elimination bracket
+    ///
+    /// Seeds are the top cut players, highest seed first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there aren't 4, 8, or 16 seeds,
+    /// or if a player is seeded more than once
+    pub fn new_double(seeds: Vec<Player>) -> std::result::Result<Self, PairingsError> {
+        Self::validate_seeds(&seeds)?;
+
+        if double_bracket(seeds.len()).is_none() {
+            return Err(PairingsError::InvalidCutSize(seeds.len()));
+        }
+
+        Ok(Self {
+            algorithm: EliminationAlgorithm::new_double(),
+            seeds,
+            rounds: vec![],
+        })
+    }
+
+    /// Gets the top cut format
+    #[inline]
+    pub fn get_format(&self) -> EliminationFormat {
+        match self.algorithm {
+            EliminationAlgorithm::Single(_) => EliminationFormat::Single,
+            EliminationAlgorithm::Double(_) => EliminationFormat::Double,
+        }
+    }
+
+    /// Gets the seeded players, highest seed first
+    #[inline]
+    pub fn get_seeds(&self) -> &Vec<Player> {
+        &self.seeds
+    }
+
+    /// Gets the seed of the given player, starting from 1
+    #[inline]
+    pub fn get_seed(&self, player: &Player) -> Option<usize> {
+        self.seeds
+            .iter()
+            .position(|seed| seed == player)
+            .map(|idx| idx + 1)
+    }
+
+    /// Gets every elimination round so far, including the current one
+    #[inline]
+    pub fn get_rounds(&self) -> &Vec<Round> {
+        &self.rounds
+    }
+
+    /// Gets the current elimination round number
+    #[inline]
+    pub fn get_current_round(&self) -> usize {
+        self.rounds.len() + 1
+    }
+
+    /// Gets the number of elimination rounds needed to find a champion
+    ///
+    /// This doesn't include a double-elimination grand final rematch
+    #[inline]
+    pub fn get_total_rounds(&self) -> usize {
+        self.algorithm.get_total_rounds(self.seeds.len())
+    }
+
+    /// Gets the final places of every player who has finished the top cut,
+    /// best first and then by seed
+    pub fn get_placements(&self) -> Vec<Placement> {
+        let mut placements = self
+            .algorithm
+            .get_placements(&self.seeds, &self.rounds)
+            .into_iter()
+            .filter_map(|(place, last_place, id)| {
+                Some(Placement {
+                    place,
+                    last_place,
+                    player: self.get_player(id)?.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        placements.sort_by_key(|placement| (placement.place, self.get_seed(&placement.player)));
+        placements
+    }
+
+    /// Gets the players who haven't been eliminated, highest seed first
+    pub fn get_remaining_players(&self) -> Vec<&Player> {
+        let placements = self.get_placements();
+        self.seeds
+            .iter()
+            .filter(|player| {
+                !placements
+                    .iter()
+                    .any(|placement| placement.get_place() > 1 && placement.get_player() == *player)
+            })
+            .collect()
+    }
+
+    /// Gets the winner of the top cut, once every round has ended
+    pub fn get_champion(&self) -> Option<&Player> {
+        self.get_placements()
+            .first()
+            .filter(|placement| placement.get_place() == 1)
+            .and_then(|placement| self.get_player(placement.get_player().get_id()))
+    }
+
+    /// Checks if the top cut has a champion
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.get_champion().is_some()
+    }
+
+    #[inline]
+    fn get_player(&self, id: &str) -> Option<&Player> {
+        self.seeds.iter().find(|player| player.get_id() == id)
+    }
+
+    #[inline]
+    fn get_seed_by_id(&self, id: &str) -> Option<usize> {
+        self.seeds.iter().position(|player| player.get_id() == id)
+    }
+
+    /// Gets the number of Corp games minus the number of Runner games
+    /// the player has played in the top cut
+    fn side_balance(&self, player: &Player) -> i64 {
+        self.rounds
+            .iter()
+            .flat_map(|round| round.get_pairings())
+            .filter_map(|pairing| pairing.get_player_side(player))
+            .map(|side| match side {
+                Side::Corp => 1,
+                Side::Runner => -1,
+            })
+            .sum()
+    }
+
+    /// Gets the side the higher seed plays against the lower seed, if the format decides it
+    fn get_side(&self, higher: &Player, lower: &Player) -> Option<Side> {
+        if let EliminationAlgorithm::Single(_) = self.algorithm {
+            return None;
+        }
+
+        // rematches swap sides
+        let rematch = self
+            .rounds
+            .iter()
+            .rev()
+            .flat_map(|round| round.get_pairings())
+            .find(|pairing| pairing.get_player_opponent(higher) == Some(lower));
+        if let Some(pairing) = rematch {
+            return pairing.get_player_side(higher).map(|side| side.opposite());
+        }
+
+        // otherwise the player who has played more Corp games plays Runner
+        match self.side_balance(higher).cmp(&self.side_balance(lower)) {
+            Ordering::Greater => Some(Side::Runner),
+            Ordering::Less => Some(Side::Corp),
+            Ordering::Equal => None,
+        }
+    }
+
+    /// Pairs the players still in the bracket
+    ///
+    /// choose_side is given the higher and lower seed of each match
+    /// where the format lets the higher seed choose, and returns their chosen side.
+    /// Pairings have the Corp player first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current round has not ended,
+    /// or if the top cut already has a champion
+    pub fn next_round(
+        &mut self,
+        mut choose_side: impl FnMut(&Player, &Player) -> Side,
+    ) -> std::result::Result<Vec<Pairing>, PairingsError> {
+        if let Some(round) = self.rounds.last() {
+            if !round.has_ended() {
+                return Err(PairingsError::RoundInProgress);
+            }
+        }
+
+        let matches = self.algorithm.next_pairings(&self.seeds);
+        if matches.is_empty() {
+            return Err(PairingsError::EliminationFinished);
+        }
+
+        let pairings = matches
+            .into_iter()
+            .map(|(player, opponent)| {
+                let (higher, lower) = if self.get_seed_by_id(player) < self.get_seed_by_id(opponent)
+                {
+                    (player, opponent)
+                } else {
+                    (opponent, player)
+                };
+
+                let higher = self.get_player(higher).unwrap().clone();
+                let lower = self.get_player(lower).unwrap().clone();
+                let side = self
+                    .get_side(&higher, &lower)
+                    .unwrap_or_else(|| choose_side(&higher, &lower));
+                match side {
+                    Side::Corp => Pairing::new_single_sided(higher, lower),
+                    Side::Runner => Pairing::new_single_sided(lower, higher),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.rounds.push(Round::new(pairings.clone()));
+
+        Ok(pairings)
+    }
+
+    /// Adds a round that was paired outside of this tournament, such as an imported one
+    ///
+    /// The pairings must be the matches the bracket expects next, in any order.
+    /// Results are the Corp player's result of each match, in the same order as the pairings,
+    /// or None if the round is still in progress
+    pub(crate) fn import_round(
+        &mut self,
+        pairings: Vec<Pairing>,
+        results: Option<Vec<Result>>,
+    ) -> std::result::Result<(), PairingsError> {
+        if let Some(round) = self.rounds.last() {
+            if !round.has_ended() {
+                return Err(PairingsError::RoundInProgress);
+            }
+        }
+
+        let matches = self.algorithm.next_pairings(&self.seeds);
+        if matches.is_empty() {
+            return Err(PairingsError::EliminationFinished);
+        }
+        if matches.len() != pairings.len() {
+            return Err(PairingsError::InvalidImport(format!(
+                "elimination round {} has {} matches instead of {}",
+                self.get_current_round(),
+                pairings.len(),
+                matches.len()
+            )));
+        }
+
+        // the bracket relies on the matches being in bracket order
+        let mut order = Vec::with_capacity(matches.len());
+        for (player, opponent) in matches {
+            let plays = |pairing: &Pairing, id: &String| {
+                pairing.get_player().get_id() == id
+                    || pairing
+                        .get_opponent()
+                        .as_ref()
+                        .is_some_and(|opponent| opponent.get_id() == id)
+            };
+            let idx = pairings
+                .iter()
+                .position(|pairing| plays(pairing, player) && plays(pairing, opponent))
+                .ok_or_else(|| {
+                    PairingsError::InvalidImport(format!(
+                        "elimination round {} is missing the match between {} and {}",
+                        self.get_current_round(),
+                        player,
+                        opponent
+                    ))
+                })?;
+            order.push(idx);
+        }
+
+        let round = order
+            .iter()
+            .map(|idx| pairings[*idx].clone())
+            .collect::<Vec<_>>();
+        self.rounds.push(Round::new(round.clone()));
+
+        if let Some(results) = results {
+            let results = order
+                .iter()
+                .zip(&round)
+                .map(|(idx, pairing)| (pairing, results[*idx]))
+                .collect::<Vec<_>>();
+            self.round_ended(results)?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates empty player reports for the current elimination round
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no round in progress
+    pub fn new_round_reports(&self) -> std::result::Result<RoundReports, PairingsError> {
+        let round = self.rounds.last().ok_or(PairingsError::RoundNotStarted)?;
+        if round.has_ended() {
+            return Err(PairingsError::RoundAlreadyEnded);
+        }
+
+        Ok(RoundReports::new(
+            self.rounds.len(),
+            round,
+            ELIMINATION_GAMES_PER_ROUND,
+        ))
+    }
+
+    /// Update internal state with round results
+    ///
+    /// Each match has a single result from the point of view of the pairing's first player
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no round in progress,
+    /// or if the results don't cover every match in the round,
+    /// have the wrong number of games for a match, or don't have a winner
+    pub fn round_ended<'a>(
+        &mut self,
+        results: impl AsRef<[(&'a Pairing, Result)]>,
+    ) -> std::result::Result<(), PairingsError> {
+        let results = results.as_ref();
+
+        let round = self
+            .rounds
+            .last_mut()
+            .ok_or(PairingsError::RoundNotStarted)?;
+        if round.has_ended() {
+            return Err(PairingsError::RoundAlreadyEnded);
+        }
+
+        validate_results(round, results, ELIMINATION_GAMES_PER_ROUND)?;
+        if let Some((pairing, _)) = results.iter().find(|(_, result)| result.is_draw()) {
+            return Err(ResultsError::NoWinner(pairing.get_player().get_id().clone()).into());
+        }
+
+        round.round_ended(results);
+        self.algorithm.round_ended(round);
+
+        Ok(())
+    }
+
+    /// Ends the current elimination round with the results confirmed by player reports
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reports are for another round,
+    /// if any pairing's results are still pending or disputed,
+    /// or if the confirmed results are invalid for the round
+    pub fn round_ended_from_reports(
+        &mut self,
+        reports: &RoundReports,
+    ) -> std::result::Result<(), PairingsError> {
+        let round = self.rounds.last().ok_or(PairingsError::RoundNotStarted)?;
+        if reports.get_round() != self.rounds.len() {
+            return Err(PairingsError::WrongRoundReports);
+        }
+        let results = reports
+            .get_confirmed_results(round)?
+            .into_iter()
+            .map(|(pairing, result)| (pairing.clone(), result))
+            .collect::<Vec<_>>();
+
+        self.round_ended(
+            results
+                .iter()
+                .map(|(pairing, result)| (pairing, *result))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(count: usize) -> Vec<Player> {
+        (1..=count)
+            .map(|seed| Player::new("Seed", seed.to_string(), None))
+            .collect()
+    }
+
+    fn elimination_seed(seeds: &[Player], player: &Player) -> usize {
+        seeds.iter().position(|seed| seed == player).unwrap()
+    }
+
+    // the higher seed always takes Corp and wins
+    fn play_round(elimination: &mut Elimination) -> Vec<Pairing> {
+        let pairings = elimination
+            .next_round(|_higher, _lower| Side::Corp)
+            .unwrap();
+        let results = pairings
+            .iter()
+            .map(|pairing| (pairing, Result::Win))
+            .collect::<Vec<_>>();
+        elimination.round_ended(results).unwrap();
+        pairings
+    }
+
+    // the higher seed always wins, and the higher seed always takes Corp when given the choice
+    fn play_higher_seeds(elimination: &mut Elimination) {
+        while !elimination.is_finished() {
+            let pairings = elimination.next_round(|_, _| Side::Corp).unwrap();
+            let results = pairings
+                .iter()
+                .map(|pairing| {
+                    let player = elimination.get_seed(pairing.get_player()).unwrap();
+                    let opponent = elimination
+                        .get_seed(pairing.get_opponent().as_ref().unwrap())
+                        .unwrap();
+                    let result = if player < opponent {
+                        Result::Win
+                    } else {
+                        Result::Loss
+                    };
+                    (pairing, result)
+                })
+                .collect::<Vec<_>>();
+            elimination.round_ended(results).unwrap();
+        }
+    }
+
+    #[test]
+    fn bracket_order_8() {
+        assert_eq!(bracket_order(2), vec![0, 1]);
+        assert_eq!(bracket_order(4), vec![0, 3, 1, 2]);
+        assert_eq!(bracket_order(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+    }
+
+    #[test]
+    fn invalid_cut_size() {
+        assert_eq!(
+            Elimination::new_single(players(1)).unwrap_err(),
+            PairingsError::NotEnoughPlayers(1)
+        );
+        assert_eq!(
+            Elimination::new_single(players(6)).unwrap_err(),
+            PairingsError::InvalidCutSize(6)
+        );
+
+        let mut seeds = players(4);
+        seeds[3] = seeds[0].clone();
+        assert_eq!(
+            Elimination::new_single(seeds.clone()).unwrap_err(),
+            PairingsError::DuplicatePlayer(seeds[0].get_id().clone())
+        );
+    }
+
+    #[test]
+    fn seeding() {
+        let seeds = players(8);
+        let mut elimination = Elimination::new_single(seeds.clone()).unwrap();
+        assert_eq!(elimination.get_total_rounds(), 3);
+
+        let pairings = elimination
+            .next_round(|_higher, _lower| Side::Corp)
+            .unwrap();
+        let matches = pairings
+            .iter()
+            .map(|pairing| {
+                (
+                    elimination.get_seed(pairing.get_player()).unwrap(),
+                    elimination
+                        .get_seed(pairing.get_opponent().as_ref().unwrap())
+                        .unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(matches, vec![(1, 8), (4, 5), (2, 7), (3, 6)]);
+    }
+
+    #[test]
+    fn higher_seed_chooses_side() {
+        let seeds = players(4);
+        let mut elimination = Elimination::new_single(seeds.clone()).unwrap();
+
+        let pairings = elimination
+            .next_round(|higher, lower| {
+                assert!(elimination_seed(&seeds, higher) < elimination_seed(&seeds, lower));
+                Side::Runner
+            })
+            .unwrap();
+        for pairing in &pairings {
+            assert_eq!(
+                pairing.get_player_side(pairing.get_player()),
+                Some(Side::Corp)
+            );
+            assert!(
+                elimination_seed(&seeds, pairing.get_player())
+                    > elimination_seed(&seeds, pairing.get_opponent().as_ref().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn champion() {
+        let seeds = players(8);
+        let mut elimination = Elimination::new_single(seeds.clone()).unwrap();
+
+        for round in 1..=3 {
+            assert_eq!(elimination.get_current_round(), round);
+            assert!(elimination.get_champion().is_none());
+            play_round(&mut elimination);
+        }
+
+        assert_eq!(elimination.get_remaining_players(), vec![&seeds[0]]);
+        assert_eq!(elimination.get_champion(), Some(&seeds[0]));
+        assert_eq!(
+            elimination.next_round(|_, _| Side::Corp).unwrap_err(),
+            PairingsError::EliminationFinished
+        );
+    }
+
+    #[test]
+    fn upsets_advance() {
+        let seeds = players(4);
+        let mut elimination = Elimination::new_single(seeds.clone()).unwrap();
+
+        // the lower seed wins every match
+        let pairings = elimination.next_round(|_, _| Side::Corp).unwrap();
+        let results = pairings
+            .iter()
+            .map(|pairing| (pairing, Result::Loss))
+            .collect::<Vec<_>>();
+        elimination.round_ended(results).unwrap();
+
+        assert_eq!(
+            elimination.get_remaining_players(),
+            vec![&seeds[2], &seeds[3]]
+        );
+
+        // seed 3 is now the higher seed
+        elimination
+            .next_round(|higher, lower| {
+                assert_eq!(higher, &seeds[2]);
+                assert_eq!(lower, &seeds[3]);
+                Side::Corp
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn invalid_results() {
+        let mut elimination = Elimination::new_single(players(4)).unwrap();
+        assert_eq!(
+            elimination.round_ended([]).unwrap_err(),
+            PairingsError::RoundNotStarted
+        );
+
+        let pairings = elimination.next_round(|_, _| Side::Corp).unwrap();
+        assert_eq!(
+            elimination.next_round(|_, _| Side::Corp).unwrap_err(),
+            PairingsError::RoundInProgress
+        );
+
+        let results = pairings
+            .iter()
+            .map(|pairing| (pairing, Result::Draw))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            elimination.round_ended(&results).unwrap_err(),
+            PairingsError::InvalidResults(ResultsError::NoWinner(
+                pairings[0].get_player().get_id().clone()
+            ))
+        );
+
+        assert_eq!(
+            elimination.round_ended(&results[..1]).unwrap_err(),
+            PairingsError::InvalidResults(ResultsError::GameCount {
+                player: pairings[1].get_player().get_id().clone(),
+                expected: 1,
+                actual: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn single_elimination_placements() {
+        let seeds = players(8);
+        let mut elimination = Elimination::new_single(seeds.clone()).unwrap();
+        play_higher_seeds(&mut elimination);
+
+        let placements = elimination
+            .get_placements()
+            .iter()
+            .map(|placement| (placement.to_string(), placement.get_player().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            placements,
+            vec![
+                ("1st".to_owned(), seeds[0].clone()),
+                ("2nd".to_owned(), seeds[1].clone()),
+                ("3-4th".to_owned(), seeds[2].clone()),
+                ("3-4th".to_owned(), seeds[3].clone()),
+                ("5-8th".to_owned(), seeds[4].clone()),
+                ("5-8th".to_owned(), seeds[5].clone()),
+                ("5-8th".to_owned(), seeds[6].clone()),
+                ("5-8th".to_owned(), seeds[7].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn placements_without_winner() {
+        let seeds = players(4);
+        let mut elimination = Elimination::new_single(seeds.clone()).unwrap();
+        play_round(&mut elimination);
+
+        // a saved state where one of the ended semi-finals was drawn
+        let mut saved = serde_json::to_value(&elimination).unwrap();
+        saved["rounds"][0]["pairings"][1]["games"][0]["result"] = "Draw".into();
+        let elimination: Elimination = serde_json::from_value(saved).unwrap();
+
+        let placements = elimination.get_placements();
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].get_player(), &seeds[3]);
+    }
+
+    #[test]
+    fn double_elimination_sizes() {
+        for size in [2, 32] {
+            assert_eq!(
+                Elimination::new_double(players(size)).unwrap_err(),
+                PairingsError::InvalidCutSize(size)
+            );
+        }
+
+        for (size, rounds) in [(4, 4), (8, 6), (16, 8)] {
+            let seeds = players(size);
+            let mut elimination = Elimination::new_double(seeds.clone()).unwrap();
+            assert_eq!(elimination.get_total_rounds(), rounds);
+
+            play_higher_seeds(&mut elimination);
+            assert_eq!(elimination.get_rounds().len(), rounds);
+            assert_eq!(elimination.get_champion(), Some(&seeds[0]));
+            assert_eq!(elimination.get_remaining_players(), vec![&seeds[0]]);
+
+            // every player finishes within their seed
+            let placements = elimination.get_placements();
+            assert_eq!(placements.len(), size);
+            for placement in placements {
+                let seed = elimination.get_seed(placement.get_player()).unwrap();
+                assert!((placement.get_place()..=placement.get_last_place()).contains(&seed));
+            }
+
+            assert_eq!(
+                elimination.next_round(|_, _| Side::Corp).unwrap_err(),
+                PairingsError::EliminationFinished
+            );
+        }
+    }
+
+    #[test]
+    fn double_elimination_8_placements() {
+        let seeds = players(8);
+        let mut elimination = Elimination::new_double(seeds.clone()).unwrap();
+        play_higher_seeds(&mut elimination);
+
+        let placements = elimination
+            .get_placements()
+            .iter()
+            .map(|placement| placement.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            placements,
+            vec!["1st", "2nd", "3rd", "4th", "5-6th", "5-6th", "7-8th", "7-8th"]
+        );
+    }
+
+    #[test]
+    fn grand_final_rematch() {
+        let seeds = players(4);
+        let mut elimination = Elimination::new_double(seeds.clone()).unwrap();
+
+        // play up to the grand final
+        for _ in 0..3 {
+            let pairings = elimination.next_round(|_, _| Side::Corp).unwrap();
+            let results = pairings
+                .iter()
+                .map(|pairing| {
+                    let player = elimination.get_seed(pairing.get_player()).unwrap();
+                    let opponent = elimination
+                        .get_seed(pairing.get_opponent().as_ref().unwrap())
+                        .unwrap();
+                    (
+                        pairing,
+                        if player < opponent {
+                            Result::Win
+                        } else {
+                            Result::Loss
+                        },
+                    )
+                })
+                .collect::<Vec<_>>();
+            elimination.round_ended(results).unwrap();
+        }
+
+        // seed 2 comes back from the losers bracket and wins the grand final
+        let grand_final = elimination.next_round(|_, _| Side::Corp).unwrap();
+        assert_eq!(grand_final.len(), 1);
+        let result = if *grand_final[0].get_player() == seeds[1] {
+            Result::Win
+        } else {
+            Result::Loss
+        };
+        elimination
+            .round_ended([(&grand_final[0], result)])
+            .unwrap();
+        assert!(!elimination.is_finished());
+        assert_eq!(
+            elimination.get_remaining_players(),
+            vec![&seeds[0], &seeds[1]]
+        );
+
+        // the rematch swaps sides
+        let rematch = elimination.next_round(|_, _| unreachable!()).unwrap();
+        assert_eq!(rematch.len(), 1);
+        assert_eq!(
+            rematch[0].get_player(),
+            grand_final[0].get_opponent().as_ref().unwrap()
+        );
+        assert_eq!(
+            rematch[0].get_opponent().as_ref(),
+            Some(grand_final[0].get_player())
+        );
+
+        elimination
+            .round_ended([(&rematch[0], Result::Win)])
+            .unwrap();
+        assert_eq!(elimination.get_champion(), Some(rematch[0].get_player()));
+        assert_eq!(elimination.get_placements().len(), 4);
+    }
+
+    #[test]
+    fn double_elimination_sides() {
+        let seeds = players(4);
+        let mut elimination = Elimination::new_double(seeds.clone()).unwrap();
+
+        // seed 1 chooses Corp and seed 2 chooses Runner
+        let pairings = elimination
+            .next_round(|higher, _| {
+                if *higher == seeds[0] {
+                    Side::Corp
+                } else {
+                    Side::Runner
+                }
+            })
+            .unwrap();
+        let results = pairings
+            .iter()
+            .map(|pairing| {
+                if seeds[..2].contains(pairing.get_player()) {
+                    (pairing, Result::Win)
+                } else {
+                    (pairing, Result::Loss)
+                }
+            })
+            .collect::<Vec<_>>();
+        elimination.round_ended(results).unwrap();
+
+        // seed 1 and seed 3 played Corp, so they play Runner
+        let pairings = elimination.next_round(|_, _| unreachable!()).unwrap();
+        for pairing in &pairings {
+            assert!(*pairing.get_player() == seeds[1] || *pairing.get_player() == seeds[3]);
+        }
+    }
+
+    #[test]
+    fn placement_display() {
+        let placement = |place, last_place| Placement {
+            place,
+            last_place,
+            player: Player::new("first", "last", None),
+        };
+        assert_eq!(placement(1, 1).to_string(), "1st");
+        assert_eq!(placement(2, 2).to_string(), "2nd");
+        assert_eq!(placement(3, 3).to_string(), "3rd");
+        assert_eq!(placement(7, 8).to_string(), "7-8th");
+        assert_eq!(placement(9, 12).to_string(), "9-12th");
+        assert_eq!(placement(17, 21).to_string(), "17-21st");
+        assert_eq!(placement(11, 11).to_string(), "11th");
+    }
+}
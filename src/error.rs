@@ -0,0 +1,206 @@
+//! Error types
+
+use std::fmt;
+
+use crate::Format;
+
+/// Round result validation errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResultsError {
+    /// A result was reported for a pairing that isn't part of the round
+    ///
+    /// Contains the id of the pairing's first player
+    UnknownPairing(String),
+
+    /// A pairing did not have the expected number of game results
+    ///
+    /// Contains the id of the pairing's first player
+    GameCount {
+        player: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A Bye was reported for a pairing with an opponent,
+    /// or something other than a Bye was reported for a bye pairing
+    ///
+    /// Contains the id of the pairing's first player
+    InvalidBye(String),
+
+    /// An elimination match was reported without a winner
+    ///
+    /// Contains the id of the pairing's first player
+    NoWinner(String),
+
+    /// A pairing's reported results have not been confirmed
+    ///
+    /// Contains the id of the pairing's first player
+    Unconfirmed(String),
+}
+
+impl fmt::Display for ResultsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownPairing(player) => {
+                write!(f, "pairing for player {} is not in this round", player)
+            }
+            Self::GameCount {
+                player,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "pairing for player {} has {} game results, expected {}",
+                player, actual, expected
+            ),
+            Self::InvalidBye(player) => {
+                write!(f, "pairing for player {} has an invalid bye result", player)
+            }
+            Self::NoWinner(player) => {
+                write!(f, "pairing for player {} has no winner", player)
+            }
+            Self::Unconfirmed(player) => {
+                write!(f, "pairing for player {} has unconfirmed results", player)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResultsError {}
+
+/// Pairings errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PairingsError {
+    /// There are not enough players to run the tournament
+    NotEnoughPlayers(usize),
+
+    /// The player is not part of the tournament
+    ///
+    /// Contains the id of the unknown player
+    UnknownPlayer(String),
+
+    /// The same player was given more than once
+    ///
+    /// Contains the id of the duplicated player
+    DuplicatePlayer(String),
+
+    /// The player has dropped from the tournament
+    ///
+    /// Contains the id of the dropped player
+    PlayerDropped(String),
+
+    /// Players can no longer be registered because the first round has started
+    TournamentStarted,
+
+    /// There is no round in progress
+    RoundNotStarted,
+
+    /// The current round has already ended
+    RoundAlreadyEnded,
+
+    /// The current round has not ended yet
+    RoundInProgress,
+
+    /// The round results were inconsistent
+    InvalidResults(ResultsError),
+
+    /// Imported tournament data could not be understood
+    ///
+    /// Contains a description of the problem
+    InvalidImport(String),
+
+    /// The tournament is too small to have a top cut
+    NoTopCut,
+
+    /// The top cut size is not supported
+    ///
+    /// Contains the number of players in the cut
+    InvalidCutSize(usize),
+
+    /// Swiss rounds can no longer be paired because the top cut has started
+    TopCutStarted,
+
+    /// The search for a valid pairing gave up before finding one
+    PairingLimitReached,
+
+    /// The top cut already has a champion
+    EliminationFinished,
+
+    /// Player reports were used with a round other than the one they were created for
+    WrongRoundReports,
+
+    /// A decklist could not be understood
+    ///
+    /// Contains a description of the problem
+    InvalidDecklist(String),
+
+    /// A card is not in the card database
+    ///
+    /// Contains the unknown name and the closest card's name, if any is close enough
+    UnknownCard {
+        name: String,
+        suggestion: Option<String>,
+    },
+
+    /// A card is not legal in the tournament's format
+    IllegalCard { card: String, format: Format },
+
+    /// The card data doesn't define the format's card pool and ban list
+    UndefinedFormat(Format),
+}
+
+impl fmt::Display for PairingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughPlayers(count) => write!(f, "not enough players ({})", count),
+            Self::UnknownPlayer(player) => write!(f, "unknown player {}", player),
+            Self::DuplicatePlayer(player) => write!(f, "duplicate player {}", player),
+            Self::PlayerDropped(player) => write!(f, "player {} has dropped", player),
+            Self::TournamentStarted => write!(f, "the tournament has already started"),
+            Self::RoundNotStarted => write!(f, "no round has been started"),
+            Self::RoundAlreadyEnded => write!(f, "the round has already ended"),
+            Self::RoundInProgress => write!(f, "the current round has not ended"),
+            Self::InvalidResults(err) => write!(f, "invalid results: {}", err),
+            Self::InvalidImport(err) => write!(f, "invalid import: {}", err),
+            Self::NoTopCut => write!(f, "the tournament has no top cut"),
+            Self::InvalidCutSize(count) => write!(f, "unsupported top cut size ({})", count),
+            Self::TopCutStarted => write!(f, "the top cut has already started"),
+            Self::PairingLimitReached => write!(f, "no valid pairing was found in time"),
+            Self::EliminationFinished => write!(f, "the top cut has already finished"),
+            Self::WrongRoundReports => write!(f, "the reports are for a different round"),
+            Self::InvalidDecklist(err) => write!(f, "invalid decklist: {}", err),
+            Self::UnknownCard {
+                name,
+                suggestion: Some(suggestion),
+            } => write!(f, "unknown card {}, did you mean {}?", name, suggestion),
+            Self::UnknownCard { name, .. } => write!(f, "unknown card {}", name),
+            Self::IllegalCard { card, format } => {
+                write!(f, "{} is not legal in {}", card, format)
+            }
+            Self::UndefinedFormat(format) => {
+                write!(
+                    f,
+                    "the card data doesn't define the {} card pool and ban list",
+                    format
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PairingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidResults(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ResultsError> for PairingsError {
+    #[inline]
+    fn from(err: ResultsError) -> Self {
+        Self::InvalidResults(err)
+    }
+}
@@ -0,0 +1,491 @@
+//! Deck legality checking against format rules
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{CardDatabase, CardType, Decklist, Faction, Format, PairingsError, Side};
+
+/// Problems that make a deck illegal for a format
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Violation {
+    /// The deck doesn't name an identity
+    MissingIdentity,
+
+    /// A card is not in the card database
+    ///
+    /// Contains the unknown name and the closest card's name, if any is close enough
+    UnknownCard {
+        name: String,
+        suggestion: Option<String>,
+    },
+
+    /// The identity isn't an identity card for the deck's side
+    InvalidIdentity(String),
+
+    /// A card belongs to the other side, or is an identity
+    WrongSide(String),
+
+    /// The deck has fewer cards than its identity allows
+    DeckSize { minimum: u32, actual: u32 },
+
+    /// The deck spends more influence than its identity allows
+    Influence { limit: u32, spent: u32 },
+
+    /// A Corp deck doesn't have the agenda points required for its size
+    ///
+    /// Decks need two points for every five cards, plus two, and may have one more
+    AgendaPoints { required: u32, actual: u32 },
+
+    /// An agenda from another faction is in the deck
+    OutOfFactionAgenda(String),
+
+    /// A card has more copies than allowed
+    TooManyCopies {
+        card: String,
+        limit: u32,
+        count: u32,
+    },
+
+    /// A card is on the format's ban list
+    Banned(String),
+
+    /// A card isn't in any of the format's packs
+    Rotated(String),
+
+    /// The card data doesn't define the format, so its card pool and ban list weren't checked
+    UncheckedFormat(Format),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingIdentity => write!(f, "the deck has no identity"),
+            Self::UnknownCard {
+                name,
+                suggestion: Some(suggestion),
+            } => write!(f, "unknown card {}, did you mean {}?", name, suggestion),
+            Self::UnknownCard { name, .. } => write!(f, "unknown card {}", name),
+            Self::InvalidIdentity(card) => write!(f, "{} is not an identity for this side", card),
+            Self::WrongSide(card) => write!(f, "{} can't be played on this side", card),
+            Self::DeckSize { minimum, actual } => {
+                write!(
+                    f,
+                    "the deck has {} cards, needs at least {}",
+                    actual, minimum
+                )
+            }
+            Self::Influence { limit, spent } => {
+                write!(f, "the deck spends {} influence, limit is {}", spent, limit)
+            }
+            Self::AgendaPoints { required, actual } => write!(
+                f,
+                "the deck has {} agenda points, needs {} or {}",
+                actual,
+                required,
+                required + 1
+            ),
+            Self::OutOfFactionAgenda(card) => {
+                write!(f, "{} is an agenda from another faction", card)
+            }
+            Self::TooManyCopies { card, limit, count } => {
+                write!(f, "{} has {} copies, limit is {}", card, count, limit)
+            }
+            Self::Banned(card) => write!(f, "{} is banned", card),
+            Self::Rotated(card) => write!(f, "{} is not in the card pool", card),
+            Self::UncheckedFormat(format) => write!(
+                f,
+                "the {} card pool and ban list could not be checked",
+                format
+            ),
+        }
+    }
+}
+
+/// A violation in one of a player's registered decks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeckViolation {
+    player: String,
+    side: Side,
+    violation: Violation,
+}
+
+impl DeckViolation {
+    /// Creates a new deck violation
+    #[inline]
+    pub(crate) fn new(player: String, side: Side, violation: Violation) -> Self {
+        Self {
+            player,
+            side,
+            violation,
+        }
+    }
+
+    /// Gets the id of the player who registered the deck
+    #[inline]
+    pub fn get_player(&self) -> &String {
+        &self.player
+    }
+
+    /// Gets the side of the deck
+    #[inline]
+    pub fn get_side(&self) -> Side {
+        self.side
+    }
+
+    /// Gets the problem with the deck
+    #[inline]
+    pub fn get_violation(&self) -> &Violation {
+        &self.violation
+    }
+}
+
+/// Gets the agenda points a Corp deck of the given size needs, which it may exceed by one
+#[inline]
+pub fn required_agenda_points(deck_size: u32) -> u32 {
+    deck_size / 5 * 2 + 2
+}
+
+/// Checks a decklist against the rules of a format
+///
+/// Unknown cards are reported and otherwise ignored, so the deck size and
+/// influence checks only count cards that were found
+///
+/// If the card data doesn't define the format, that's reported once instead of
+/// checking each card's rotation and bans
+pub fn check_decklist(
+    database: &CardDatabase,
+    decklist: &Decklist,
+    side: Side,
+    format: Format,
+) -> Vec<Violation> {
+    let mut violations = vec![];
+    let find = |name: &str, violations: &mut Vec<Violation>| match database.find_card(name) {
+        Ok(card) => Some(card),
+        Err(PairingsError::UnknownCard { name, suggestion }) => {
+            violations.push(Violation::UnknownCard { name, suggestion });
+            None
+        }
+        Err(_) => None,
+    };
+
+    // JSON decklists list their identity with the other cards
+    let identity = match decklist.get_identity() {
+        Some(identity) => find(identity, &mut violations),
+        None => decklist
+            .get_cards()
+            .iter()
+            .filter_map(|deck_card| database.get_card(deck_card.get_card()))
+            .find(|card| card.get_type() == CardType::Identity),
+    };
+    let identity = match identity {
+        Some(card) if card.get_type() == CardType::Identity && card.get_side() == side => {
+            Some(card)
+        }
+        Some(card) => {
+            violations.push(Violation::InvalidIdentity(card.get_title().clone()));
+            None
+        }
+        None if decklist.get_identity().is_none() => {
+            violations.push(Violation::MissingIdentity);
+            None
+        }
+        None => None,
+    };
+
+    // combine repeated entries for the same card
+    let mut counts = HashMap::new();
+    let mut cards = vec![];
+    for deck_card in decklist.get_cards() {
+        let Some(card) = find(deck_card.get_card(), &mut violations) else {
+            continue;
+        };
+        if Some(card) == identity {
+            continue;
+        }
+        if !counts.contains_key(card.get_title()) {
+            cards.push(card);
+        }
+        *counts.entry(card.get_title()).or_insert(0) += deck_card.get_count();
+    }
+
+    if database.has_format(format) {
+        for card in identity.into_iter().chain(cards.iter().copied()) {
+            if database.is_banned(card, format) == Ok(true) {
+                violations.push(Violation::Banned(card.get_title().clone()));
+            } else if database.is_in_pool(card, format) == Ok(false) {
+                violations.push(Violation::Rotated(card.get_title().clone()));
+            }
+        }
+    } else {
+        violations.push(Violation::UncheckedFormat(format));
+    }
+
+    let mut deck_size = 0;
+    let mut influence = 0;
+    let mut agenda_points = 0;
+    for card in &cards {
+        let count = counts[card.get_title()];
+        deck_size += count;
+
+        if card.get_side() != side || card.get_type() == CardType::Identity {
+            violations.push(Violation::WrongSide(card.get_title().clone()));
+            continue;
+        }
+        if count > card.get_deck_limit() {
+            violations.push(Violation::TooManyCopies {
+                card: card.get_title().clone(),
+                limit: card.get_deck_limit(),
+                count,
+            });
+        }
+
+        let out_of_faction = identity.is_some_and(|identity| {
+            card.get_faction() != identity.get_faction()
+                && !matches!(
+                    card.get_faction(),
+                    Faction::NeutralCorp | Faction::NeutralRunner
+                )
+        });
+        if card.get_type() == CardType::Agenda {
+            agenda_points += card.get_agenda_points().unwrap_or(0) * count;
+            if out_of_faction {
+                violations.push(Violation::OutOfFactionAgenda(card.get_title().clone()));
+            }
+        } else if out_of_faction {
+            influence += card.get_influence() * count;
+        }
+    }
+
+    let Some(identity) = identity else {
+        return violations;
+    };
+    if let Some(minimum) = identity.get_minimum_deck_size() {
+        if deck_size < minimum {
+            violations.push(Violation::DeckSize {
+                minimum,
+                actual: deck_size,
+            });
+        }
+    }
+    if let Some(limit) = identity.get_influence_limit() {
+        if influence > limit {
+            violations.push(Violation::Influence {
+                limit,
+                spent: influence,
+            });
+        }
+    }
+    if side == Side::Corp {
+        let required = required_agenda_points(deck_size);
+        if agenda_points != required && agenda_points != required + 1 {
+            violations.push(Violation::AgendaPoints {
+                required,
+                actual: agenda_points,
+            });
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DeckCard;
+
+    use super::*;
+
+    const CARDS: &str = r#"{
+        "version": "test",
+        "data": [
+            { "title": "Anarch: Tester", "type_code": "identity", "faction_code": "anarch", "pack_code": "new", "minimum_deck_size": 10, "influence_limit": 4, "deck_limit": 1 },
+            { "title": "Weyland: Tester", "type_code": "identity", "faction_code": "weyland-consortium", "pack_code": "new", "minimum_deck_size": 10, "influence_limit": 4, "deck_limit": 1 },
+            { "title": "Neutral Gamble", "type_code": "event", "faction_code": "neutral-runner", "pack_code": "new", "faction_cost": 0 },
+            { "title": "Shaper Program", "type_code": "program", "faction_code": "shaper", "pack_code": "new", "faction_cost": 2 },
+            { "title": "Old Program", "type_code": "program", "faction_code": "anarch", "pack_code": "old", "faction_cost": 1 },
+            { "code": "00006", "title": "Banned Gamble", "type_code": "event", "faction_code": "anarch", "pack_code": "new", "faction_cost": 1 },
+            { "title": "Only One", "type_code": "hardware", "faction_code": "anarch", "pack_code": "new", "faction_cost": 1, "deck_limit": 1 },
+            { "title": "Weyland Agenda", "type_code": "agenda", "faction_code": "weyland-consortium", "pack_code": "new", "agenda_points": 2 },
+            { "title": "Jinteki Agenda", "type_code": "agenda", "faction_code": "jinteki", "pack_code": "new", "agenda_points": 2 },
+            { "title": "Neutral Agenda", "type_code": "agenda", "faction_code": "neutral-corp", "pack_code": "new", "agenda_points": 1 },
+            { "title": "Neutral Wall", "type_code": "ice", "faction_code": "neutral-corp", "pack_code": "new", "faction_cost": 0 },
+            { "title": "Neutral Hedge", "type_code": "operation", "faction_code": "neutral-corp", "pack_code": "new", "faction_cost": 0 }
+        ]
+    }"#;
+
+    const PACKS: &str = r#"{
+        "data": [
+            { "code": "old", "cycle_code": "old" },
+            { "code": "new", "cycle_code": "new" }
+        ]
+    }"#;
+
+    const CYCLES: &str = r#"{
+        "data": [
+            { "code": "old", "rotated": true },
+            { "code": "new", "rotated": false }
+        ]
+    }"#;
+
+    const MWL: &str = r#"{
+        "data": [
+            { "name": "Old List", "active": false, "cards": {} },
+            { "name": "Test List", "active": true, "cards": { "00006": { "deck_limit": 0 } } }
+        ]
+    }"#;
+
+    fn database() -> CardDatabase {
+        CardDatabase::from_netrunnerdb(CARDS, PACKS, CYCLES, MWL).unwrap()
+    }
+
+    fn runner_deck(cards: &[(&str, u32)]) -> Decklist {
+        Decklist::new(
+            Some("Anarch: Tester".to_owned()),
+            cards
+                .iter()
+                .map(|(card, count)| DeckCard::new(*card, *count))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn legal() {
+        let database = database();
+        // empty entries for a card are combined with its other entries, before or after them
+        let decklist = runner_deck(&[
+            ("Neutral Gamble", 3),
+            ("Old Program", 0),
+            ("Shaper Program", 2),
+            ("Neutral Gamble", 0),
+            ("Banned Gamble", 3),
+            ("Old Program", 3),
+        ]);
+        assert_eq!(
+            check_decklist(&database, &decklist, Side::Runner, Format::Eternal),
+            vec![]
+        );
+        assert_eq!(
+            check_decklist(&database, &decklist, Side::Runner, Format::Standard),
+            vec![
+                Violation::Rotated("Old Program".to_owned()),
+                Violation::Banned("Banned Gamble".to_owned()),
+            ]
+        );
+
+        let corp = Decklist::new(
+            Some("Weyland: Tester".to_owned()),
+            vec![
+                DeckCard::new("Weyland Agenda", 3),
+                DeckCard::new("Neutral Agenda", 1),
+                DeckCard::new("Neutral Wall", 3),
+                DeckCard::new("Neutral Hedge", 3),
+                DeckCard::new("Hedge Fund", 3),
+            ],
+        );
+        assert_eq!(
+            check_decklist(&database, &corp, Side::Corp, Format::Standard),
+            vec![Violation::UnknownCard {
+                name: "Hedge Fund".to_owned(),
+                suggestion: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn runner_violations() {
+        let database = database();
+        let decklist = runner_deck(&[
+            ("Neutral Gamble", 3),
+            ("Shaper Program", 3),
+            ("Only One", 2),
+            ("Neutral Wall", 1),
+        ]);
+        assert_eq!(
+            check_decklist(&database, &decklist, Side::Runner, Format::Standard),
+            vec![
+                Violation::TooManyCopies {
+                    card: "Only One".to_owned(),
+                    limit: 1,
+                    count: 2,
+                },
+                Violation::WrongSide("Neutral Wall".to_owned()),
+                Violation::DeckSize {
+                    minimum: 10,
+                    actual: 9,
+                },
+                Violation::Influence { limit: 4, spent: 6 },
+            ]
+        );
+
+        let decklist = Decklist::new(None, vec![DeckCard::new("Neutral Gamble", 10)]);
+        assert!(
+            check_decklist(&database, &decklist, Side::Runner, Format::Standard)
+                .contains(&Violation::MissingIdentity)
+        );
+        assert!(
+            check_decklist(&database, &decklist, Side::Corp, Format::Standard)
+                .contains(&Violation::MissingIdentity)
+        );
+    }
+
+    #[test]
+    fn corp_violations() {
+        let database = database();
+        let decklist = Decklist::new(
+            Some("Weyland: Tester".to_owned()),
+            vec![
+                DeckCard::new("Weyland Agenda", 3),
+                DeckCard::new("Jinteki Agenda", 1),
+                DeckCard::new("Neutral Wall", 3),
+                DeckCard::new("Neutral Agenda", 3),
+            ],
+        );
+        assert_eq!(
+            check_decklist(&database, &decklist, Side::Corp, Format::Standard),
+            vec![
+                Violation::OutOfFactionAgenda("Jinteki Agenda".to_owned()),
+                Violation::AgendaPoints {
+                    required: 6,
+                    actual: 11,
+                },
+            ]
+        );
+
+        assert_eq!(required_agenda_points(44), 18);
+        assert_eq!(required_agenda_points(45), 20);
+        assert_eq!(
+            check_decklist(&database, &decklist, Side::Runner, Format::Standard)[0],
+            Violation::InvalidIdentity("Weyland: Tester".to_owned())
+        );
+    }
+
+    #[test]
+    fn unchecked_format() {
+        // a card dump without pack, cycle and ban list data
+        let dump = CardDatabase::from_json(CARDS).unwrap();
+        let decklist = runner_deck(&[
+            ("Neutral Gamble", 3),
+            ("Old Program", 3),
+            ("Banned Gamble", 3),
+            ("Only One", 2),
+        ]);
+        assert_eq!(
+            check_decklist(&dump, &decklist, Side::Runner, Format::Standard),
+            vec![
+                Violation::UncheckedFormat(Format::Standard),
+                Violation::TooManyCopies {
+                    card: "Only One".to_owned(),
+                    limit: 1,
+                    count: 2,
+                },
+            ]
+        );
+
+        // NetrunnerDB data has no Startup card pool
+        let violations = check_decklist(&database(), &decklist, Side::Runner, Format::Startup);
+        assert!(violations.contains(&Violation::UncheckedFormat(Format::Startup)));
+        assert!(!violations
+            .iter()
+            .any(|violation| matches!(violation, Violation::Rotated(_) | Violation::Banned(_))));
+    }
+}
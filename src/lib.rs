@@ -2,87 +2,408 @@
 
 #![allow(dead_code)]
 
+mod cards;
+pub mod cobra;
+mod deck;
+mod elimination;
+mod error;
+mod legality;
+pub mod meta;
+pub mod nrtm;
 mod pairing;
 mod player;
+pub mod ratings;
+mod reporting;
 mod round;
+mod scoring;
+mod standing;
+mod stats;
 
+use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
-use rand::{seq::SliceRandom, thread_rng};
-
+use rand::{seq::SliceRandom, thread_rng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+pub use cards::*;
+pub use deck::*;
+pub use elimination::*;
+pub use error::*;
+pub use legality::*;
 pub use pairing::*;
 pub use player::*;
+pub use reporting::*;
 pub use round::*;
+pub use scoring::*;
+pub use standing::*;
+
+use stats::*;
+
+/// Number of games played each Swiss round
+const SWISS_GAMES_PER_ROUND: usize = 2;
+
+/// Number of games played each Single-sided Swiss round
+const SINGLE_SWISS_GAMES_PER_ROUND: usize = 1;
+
+/// Cost added to pairings that would rematch players
+const REMATCH_COST: u64 = 1_000_000;
+
+/// Weight applied to the square of each player's side balance in Single-sided Swiss
+///
+/// This is higher than the cost of floating a player by a single win
+/// so that side balance is preferred over staying within a score group
+const SIDE_BALANCE_WEIGHT: u64 = 4;
+
+/// Furthest a player's side balance may drift in Single-sided Swiss
+/// before they must play the other side
+const MAX_SIDE_BALANCE: i64 = 2;
+
+/// Maximum number of steps in the search for the lowest cost pairing
+const MAX_PAIRING_STEPS: usize = 100_000;
+
+/// Ensures the results cover every pairing in the round with the expected number of games
+///
+/// Results are from the point of view of each pairing's first player,
+/// and their opponent is credited with the opposite result
+fn validate_results(
+    round: &Round,
+    results: &[(&Pairing, Result)],
+    games_per_round: usize,
+) -> std::result::Result<(), ResultsError> {
+    for (pairing, result) in results {
+        if !round.get_pairings().contains(pairing) {
+            return Err(ResultsError::UnknownPairing(
+                pairing.get_player().get_id().clone(),
+            ));
+        }
+
+        let bye = pairing.get_opponent().is_none();
+        if bye != (*result == Result::Bye) {
+            return Err(ResultsError::InvalidBye(
+                pairing.get_player().get_id().clone(),
+            ));
+        }
+    }
 
-/// Swiss style player stats
-#[derive(Debug)]
-struct SwissStats {
-    score: u64,
+    for pairing in round.get_pairings() {
+        let actual = results
+            .iter()
+            .filter(|(result_pairing, _)| *result_pairing == pairing)
+            .count();
+        if actual != games_per_round {
+            return Err(ResultsError::GameCount {
+                player: pairing.get_player().get_id().clone(),
+                expected: games_per_round,
+                actual,
+            });
+        }
+    }
 
-    // TODO: should this just track the round opponents?
-    // that might make score calculation easier
-    rounds_played: u64,
+    Ok(())
 }
 
-impl SwissStats {
-    fn average_points_per_rounds(&self) -> f32 {
-        if self.rounds_played == 0 {
-            return 0.0;
+/// Adds each game result to both players' stats
+fn record_results<T: AsMut<SwissStats> + Default>(
+    stats: &mut HashMap<String, T>,
+    results: &[(&Pairing, Result)],
+    scoring: &ScoringSystem,
+) {
+    let mut games = HashMap::new();
+    for (pairing, result) in results {
+        let game = games.entry(pairing.get_player().get_id()).or_insert(0);
+        let side = pairing.get_game_side(*game);
+        *game += 1;
+
+        stats
+            .entry(pairing.get_player().get_id().clone())
+            .or_default()
+            .as_mut()
+            .add_result(*result, side, scoring);
+
+        if let Some(opponent) = pairing.get_opponent() {
+            stats
+                .entry(opponent.get_id().clone())
+                .or_default()
+                .as_mut()
+                .add_result(result.opposite(), side.map(|side| side.opposite()), scoring);
         }
-        self.score as f32 / self.rounds_played as f32
     }
+}
 
-    fn strength_of_schedule(&self, player: &Player, rounds: impl AsRef<[Round]>) -> f32 {
-        let rounds = rounds.as_ref();
-        if rounds.is_empty() {
-            return 0.0;
+/// Gets the set of (player id, opponent id) pairs that have already played each other
+fn played_opponents(rounds: &[Round]) -> HashSet<(&str, &str)> {
+    let mut played = HashSet::new();
+    for round in rounds {
+        for pairing in round.get_pairings() {
+            if let Some(opponent) = pairing.get_opponent() {
+                let player = pairing.get_player();
+                played.insert((player.get_id().as_str(), opponent.get_id().as_str()));
+                played.insert((opponent.get_id().as_str(), player.get_id().as_str()));
+            }
         }
+    }
+    played
+}
 
-        let mut total = 0.0;
-        for round in rounds {
-            for pairing in round.get_pairings() {
-                if let Some(_opponent) = pairing.get_player_opponent(player) {
-                    // TODO: add the opponents average_points_per_rounds to the total
-                    total += 0.0;
-                }
+/// Groups ranked players by score and randomizes the order within each group
+fn shuffle_score_groups(
+    players: Vec<Player>,
+    score: impl Fn(&Player) -> u64,
+    rng: &mut impl Rng,
+) -> Vec<Player> {
+    let mut ordered = Vec::with_capacity(players.len());
+    for (_, group) in &players.into_iter().group_by(|player| score(player)) {
+        let mut group = group.collect::<Vec<_>>();
+        group.shuffle(rng);
+        ordered.extend(group);
+    }
+    ordered
+}
+
+/// Sorts players by score and tiebreakers, highest ranked first
+fn rank_swiss_players<T: AsRef<SwissStats>>(
+    stats: &HashMap<String, T>,
+    players: &mut [Player],
+) -> std::result::Result<(), PairingsError> {
+    if let Some(player) = players
+        .iter()
+        .find(|player| !stats.contains_key(player.get_id()))
+    {
+        return Err(PairingsError::UnknownPlayer(player.get_id().clone()));
+    }
+
+    players.sort_by(|x, y| {
+        let xs = stats[x.get_id()].as_ref();
+        let ys = stats[y.get_id()].as_ref();
+
+        // order by score first
+        let score = ys.score.cmp(&xs.score);
+        if score != Ordering::Equal {
+            return score;
+        }
+
+        // break ties by sos
+        let sos = ys.sos.total_cmp(&xs.sos);
+        if sos != Ordering::Equal {
+            return sos;
+        }
+
+        // break further ties by extended sos
+        let esos = ys.esos.total_cmp(&xs.esos);
+        if esos != Ordering::Equal {
+            return esos;
+        }
+
+        // finally, randomize
+        xs.tiebreak.cmp(&ys.tiebreak)
+    });
+
+    Ok(())
+}
+
+/// Cost of pairing two players in Single-sided Swiss
+///
+/// Returns the cost along with the side the first player should play,
+/// or None for the side if either side is equally good.
+/// In strict mode, players may not play the same opponent on the same side twice
+/// or let their side balance drift too far, otherwise those are just very expensive.
+fn single_swiss_cost(
+    (x_id, x): (&str, &SingleSwissStats),
+    (y_id, y): (&str, &SingleSwissStats),
+    strict: bool,
+) -> Option<(u64, Option<Side>)> {
+    let side_cost = |side: Side| {
+        let mut cost = x.swiss.score.abs_diff(y.swiss.score).pow(2);
+
+        // rematches on the other side are a last resort
+        // and exact rematches are even worse
+        if x.has_played(y_id, side) {
+            if strict {
+                return None;
+            }
+            cost += REMATCH_COST * 2;
+        } else if x.has_played(y_id, side.opposite()) {
+            cost += REMATCH_COST;
+        }
+
+        let offset = match side {
+            Side::Corp => 1,
+            Side::Runner => -1,
+        };
+        let x_balance = x.side_balance() + offset;
+        let y_balance = y.side_balance() - offset;
+        if x_balance.abs() > MAX_SIDE_BALANCE || y_balance.abs() > MAX_SIDE_BALANCE {
+            if strict {
+                return None;
             }
+            cost += REMATCH_COST;
         }
+        cost += SIDE_BALANCE_WEIGHT * (x_balance.pow(2) + y_balance.pow(2)) as u64;
+
+        Some(cost)
+    };
+
+    // sanity check that the stats agree with each other
+    debug_assert_eq!(
+        x.has_played(y_id, Side::Corp),
+        y.has_played(x_id, Side::Runner)
+    );
+
+    match (side_cost(Side::Corp), side_cost(Side::Runner)) {
+        (None, None) => None,
+        (Some(corp), None) => Some((corp, Some(Side::Corp))),
+        (None, Some(runner)) => Some((runner, Some(Side::Runner))),
+        (Some(corp), Some(runner)) => match corp.cmp(&runner) {
+            Ordering::Less => Some((corp, Some(Side::Corp))),
+            Ordering::Greater => Some((runner, Some(Side::Runner))),
+            Ordering::Equal => Some((corp, None)),
+        },
+    }
+}
 
-        total / rounds.len() as f32
+/// Pairs up count players (by index) with the lowest strict cost, falling back to
+/// the lenient cost if no strict pairing exists or none was found in time
+///
+/// Every two players must have a lenient cost, such as by allowing rematches
+fn pair_players_with_fallback(
+    count: usize,
+    strict: impl Fn(usize, usize) -> Option<u64>,
+    lenient: impl Fn(usize, usize) -> Option<u64>,
+) -> std::result::Result<Vec<(usize, usize)>, PairingsError> {
+    match pair_players(count, strict) {
+        Ok(Some(paired)) => Ok(paired),
+        Ok(None) | Err(PairingsError::PairingLimitReached) => Ok(pair_players(count, lenient)?
+            .expect("players can always be paired if rematches are allowed")),
+        Err(error) => Err(error),
     }
+}
 
-    fn extended_strength_of_schedule(&self, player: &Player, rounds: impl AsRef<[Round]>) -> f32 {
-        let rounds = rounds.as_ref();
+/// Pairs up count players (by index) with the lowest total cost
+///
+/// This is a branch and bound search: each unpaired player is tried against their
+/// cheapest opponents first, and partial pairings that can't beat the best complete
+/// pairing found so far are abandoned, ties going to the pairing found first.
+/// A cost of None means the two players can not be paired.
+///
+/// If the search runs out of steps the best complete pairing found so far is used.
+/// Returns None if no complete pairing exists, or an error if the search ran out
+/// of steps before finding one
+fn pair_players(
+    count: usize,
+    cost: impl Fn(usize, usize) -> Option<u64>,
+) -> std::result::Result<Option<Vec<(usize, usize)>>, PairingsError> {
+    struct Search {
+        costs: Vec<Vec<Option<u64>>>,
+        paired: Vec<bool>,
+        pairs: Vec<(usize, usize)>,
+        best: Option<(u64, Vec<(usize, usize)>)>,
+        steps: usize,
+    }
 
-        let mut opponent_count = 0;
-        let mut total = 0.0;
-        for round in rounds {
-            for pairing in round.get_pairings() {
-                if let Some(_opponent) = pairing.get_player_opponent(player) {
-                    // TODO: add the opponents strength_of_schedule to the total
-                    total += 0.0;
+    impl Search {
+        /// Gets a lower bound on the cost of pairing the unpaired players,
+        /// or None if one of them has no possible opponent
+        fn lower_bound(&self) -> Option<u64> {
+            let unpaired = (0..self.paired.len())
+                .filter(|x| !self.paired[*x])
+                .collect::<Vec<_>>();
+
+            // every pair costs at least half of each player's cheapest opponent
+            let mut bound = 0;
+            for x in &unpaired {
+                bound += unpaired
+                    .iter()
+                    .filter(|y| *y != x)
+                    .filter_map(|y| self.costs[*x][*y])
+                    .min()?;
+            }
+            Some(bound / 2)
+        }
 
-                    opponent_count += 1;
+        /// Returns false if the search ran out of steps
+        fn pair(&mut self, total: u64) -> bool {
+            let Some(x) = self.paired.iter().position(|paired| !paired) else {
+                if self.best.as_ref().is_none_or(|(best, _)| total < *best) {
+                    self.best = Some((total, self.pairs.clone()));
                 }
+                return true;
+            };
+
+            self.steps += 1;
+            if self.steps > MAX_PAIRING_STEPS {
+                return false;
             }
-        }
 
-        if opponent_count == 0 {
-            return 0.0;
+            let Some(bound) = self.lower_bound() else {
+                return true;
+            };
+            if self
+                .best
+                .as_ref()
+                .is_some_and(|(best, _)| total + bound >= *best)
+            {
+                return true;
+            }
+
+            let mut candidates = (x + 1..self.paired.len())
+                .filter(|y| !self.paired[*y])
+                .filter_map(|y| self.costs[x][y].map(|cost| (cost, y)))
+                .collect::<Vec<_>>();
+            candidates.sort();
+
+            self.paired[x] = true;
+            for (cost, y) in candidates {
+                self.paired[y] = true;
+                self.pairs.push((x, y));
+
+                let finished = self.pair(total + cost);
+
+                self.pairs.pop();
+                self.paired[y] = false;
+
+                if !finished {
+                    self.paired[x] = false;
+                    return false;
+                }
+            }
+            self.paired[x] = false;
+
+            true
         }
-        total / opponent_count as f32
     }
-}
 
-/// Single-sided swiss player stats
-#[derive(Debug)]
-struct SingleSwissStats {
-    score: u64,
+    let mut search = Search {
+        costs: (0..count)
+            .map(|x| {
+                (0..count)
+                    .map(|y| {
+                        if x == y {
+                            None
+                        } else {
+                            cost(x.min(y), x.max(y))
+                        }
+                    })
+                    .collect()
+            })
+            .collect(),
+        paired: vec![false; count],
+        pairs: Vec::with_capacity(count / 2),
+        best: None,
+        steps: 0,
+    };
+    let finished = search.pair(0);
+
+    match search.best {
+        Some((_, pairs)) => Ok(Some(pairs)),
+        None if finished => Ok(None),
+        None => Err(PairingsError::PairingLimitReached),
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 enum PairingsAlgorithm {
     /// Swiss style pairings
@@ -113,10 +434,13 @@ impl PairingsAlgorithm {
     }
 
     #[inline]
-    fn get_total_rounds(&self, player_count: usize) -> usize {
-        match self {
+    fn get_total_rounds(&self, player_count: usize) -> std::result::Result<usize, PairingsError> {
+        if player_count < 2 {
+            return Err(PairingsError::NotEnoughPlayers(player_count));
+        }
+
+        Ok(match self {
             Self::Swiss(_) => match player_count {
-                0..=1 => panic!("not enough players"),
                 2..=9 => 3,
                 10..=32 => 4,
                 33..=56 => 5,
@@ -126,138 +450,430 @@ impl PairingsAlgorithm {
                 193..=256 => 8,
                 _ => 9,
             },
+            // enough rounds for a single undefeated player, plus one,
+            // rounded up to an even number so everyone can play each side equally
             Self::SingleSwiss(_) => match player_count {
-                0..=1 => panic!("not enough players"),
-                _ => todo!(),
+                2..=8 => 4,
+                9..=32 => 6,
+                33..=128 => 8,
+                _ => 10,
             },
-        }
+        })
     }
 
     #[inline]
-    fn get_top_cut(&self, player_count: usize) -> Option<usize> {
-        match self {
+    fn get_top_cut(
+        &self,
+        player_count: usize,
+    ) -> std::result::Result<Option<usize>, PairingsError> {
+        if player_count < 2 {
+            return Err(PairingsError::NotEnoughPlayers(player_count));
+        }
+
+        Ok(match self {
             Self::Swiss(_) => match player_count {
-                0..=1 => panic!("not enough players"),
                 2..=15 => None,
                 16..=24 => Some(4),
                 25..=128 => Some(8),
                 _ => Some(16),
             },
             Self::SingleSwiss(_) => match player_count {
-                0..=1 => panic!("not enough players"),
-                _ => todo!(),
+                2..=11 => None,
+                12..=24 => Some(4),
+                25..=128 => Some(8),
+                _ => Some(16),
             },
+        })
+    }
+
+    /// Starts tracking stats for the given player if they are new
+    fn add_player(&mut self, player: &Player, tiebreak: u64) {
+        let new_stats = || SwissStats {
+            tiebreak,
+            ..Default::default()
+        };
+
+        match self {
+            Self::Swiss(stats) => {
+                stats
+                    .entry(player.get_id().clone())
+                    .or_insert_with(new_stats);
+            }
+            Self::SingleSwiss(stats) => {
+                stats
+                    .entry(player.get_id().clone())
+                    .or_insert_with(|| SingleSwissStats {
+                        swiss: new_stats(),
+                        ..Default::default()
+                    });
+            }
         }
     }
 
-    fn rank_players(&self, players: &mut [Player], rounds: impl AsRef<[Round]>) {
-        let rounds = rounds.as_ref();
+    /// Credits the player for rounds they were not in the tournament for
+    fn missed_rounds(
+        &mut self,
+        player: &Player,
+        rounds: u64,
+        late_join_scoring: LateJoinScoring,
+        scoring: &ScoringSystem,
+    ) {
+        let games_per_round = match self {
+            Self::Swiss(_) => SWISS_GAMES_PER_ROUND,
+            Self::SingleSwiss(_) => SINGLE_SWISS_GAMES_PER_ROUND,
+        } as u64;
+
+        let stats = match self {
+            Self::Swiss(stats) => stats.get_mut(player.get_id()),
+            Self::SingleSwiss(stats) => stats.get_mut(player.get_id()).map(|s| &mut s.swiss),
+        };
+        let Some(stats) = stats else {
+            return;
+        };
+
+        stats.rounds_played += rounds;
+        let result = match late_join_scoring {
+            LateJoinScoring::Loss => Result::Loss,
+            LateJoinScoring::Bye => Result::Bye,
+        };
+        for _ in 0..games_per_round * rounds {
+            stats.add_result(result, None, scoring);
+        }
+    }
 
+    /// Gets the player's current score
+    #[inline]
+    fn get_score(&self, player: &Player) -> u64 {
         match self {
-            Self::Swiss(stats) => players.sort_by(|x, y| {
-                let xs = stats.get(x.get_id()).unwrap();
-                let ys = stats.get(y.get_id()).unwrap();
-
-                // order by score first
-                let score = xs.score.cmp(&ys.score);
-                if score != Ordering::Equal {
-                    return score;
-                }
+            Self::Swiss(stats) => stats.get(player.get_id()).map_or(0, |s| s.score),
+            Self::SingleSwiss(stats) => stats.get(player.get_id()).map_or(0, |s| s.swiss.score),
+        }
+    }
+
+    /// Gets the player's Swiss stats
+    #[inline]
+    fn get_stats(&self, player: &Player) -> Option<&SwissStats> {
+        match self {
+            Self::Swiss(stats) => stats.get(player.get_id()),
+            Self::SingleSwiss(stats) => stats.get(player.get_id()).map(|s| &s.swiss),
+        }
+    }
 
-                // break ties by sos
-                let xsos = xs.strength_of_schedule(x, rounds);
-                let ysos = ys.strength_of_schedule(y, rounds);
+    /// Gets the number of byes the player has had
+    #[inline]
+    fn get_byes(&self, player: &Player) -> u64 {
+        match self {
+            Self::Swiss(stats) => stats.get(player.get_id()).map_or(0, |s| s.byes),
+            Self::SingleSwiss(stats) => stats.get(player.get_id()).map_or(0, |s| s.swiss.byes),
+        }
+    }
 
-                let sos = xsos.partial_cmp(&ysos).unwrap();
-                if sos != Ordering::Equal {
-                    return sos;
-                }
+    /// Removes the player who should get a bye from the ranked players
+    ///
+    /// The bye goes to the lowest ranked player who has had the fewest byes,
+    /// so nobody gets a second bye until everyone has had one.
+    /// Returns None if there is an even number of players.
+    fn take_bye(&self, players: &mut Vec<Player>) -> Option<Player> {
+        if players.len().is_multiple_of(2) {
+            return None;
+        }
 
-                // break further ties by extended sos
-                let xesos = xs.extended_strength_of_schedule(x, rounds);
-                let yesos = ys.extended_strength_of_schedule(y, rounds);
+        let fewest = players.iter().map(|player| self.get_byes(player)).min()?;
+        let idx = players
+            .iter()
+            .rposition(|player| self.get_byes(player) == fewest)?;
 
-                let esos = xesos.partial_cmp(&yesos).unwrap();
-                if esos != Ordering::Equal {
-                    return esos;
-                }
+        Some(players.remove(idx))
+    }
 
-                // finally, randomize
-                // TODO:
-                Ordering::Equal
-            }),
-            Self::SingleSwiss(_) => todo!(),
+    fn rank_players(&self, players: &mut [Player]) -> std::result::Result<(), PairingsError> {
+        match self {
+            Self::Swiss(stats) => rank_swiss_players(stats, players),
+            Self::SingleSwiss(stats) => rank_swiss_players(stats, players),
         }
     }
 
-    fn next_pairings(&self, mut players: Vec<Player>, rounds: impl AsRef<[Round]>) -> Vec<Pairing> {
-        let mut rng = thread_rng();
+    fn next_pairings(
+        &self,
+        mut players: Vec<Player>,
+        rounds: impl AsRef<[Round]>,
+        rng: &mut impl Rng,
+    ) -> std::result::Result<Vec<Pairing>, PairingsError> {
+        let rounds = rounds.as_ref();
 
         let mut pairings = vec![];
-        match self {
-            Self::Swiss(stats) => {
-                if rounds.as_ref().is_empty() {
-                    // first round is always random pairing
-                    players.shuffle(&mut rng);
-
-                    // handle bye (odd number of players)
-                    if players.len() % 2 != 0 {
-                        let player = players.pop().unwrap();
-                        pairings.push(Pairing::new_bye(player));
-                    }
+        if rounds.is_empty() {
+            // first round is always random pairing
+            players.shuffle(rng);
 
-                    for pairing in players.chunks(2) {
-                        pairings.push(Pairing::from_slice(pairing));
-                    }
-                } else {
-                    self.rank_players(&mut players, &rounds);
-
-                    // group players by score
-                    let mut groupings = vec![];
-                    for (_, group) in &players
-                        .iter()
-                        .group_by(|player| stats.get(player.get_id()).unwrap().score)
-                    {
-                        groupings.push(group.collect::<Vec<_>>());
+            if let Some(player) = self.take_bye(&mut players) {
+                pairings.push(Pairing::new_bye(player));
+            }
+
+            for pairing in players.chunks(2) {
+                pairings.push(match self {
+                    Self::Swiss(_) => Pairing::from_slice(pairing),
+                    // sides are random as well
+                    Self::SingleSwiss(_) => {
+                        Pairing::new_single_sided(pairing[0].clone(), pairing[1].clone())
                     }
+                });
+            }
 
-                    // pair players within their groups
-                    for mut group in groupings {
-                        group.shuffle(&mut rng);
+            return Ok(pairings);
+        }
 
-                        // TODO: these pairings should be random
-                        // and then corrected so that no player plays the same player twice
-                        // dipping down into the next grouping where needed
-                        // also, if an odd number of players haven't had a bye
-                        // then the lowest ranked player who hasn't had a bye gets it
-                    }
+        self.rank_players(&mut players)?;
+
+        if let Some(player) = self.take_bye(&mut players) {
+            pairings.push(Pairing::new_bye(player));
+        }
+
+        let ordered = shuffle_score_groups(players, |player| self.get_score(player), rng);
+
+        match self {
+            Self::Swiss(_) => {
+                let played = played_opponents(rounds);
+                let rematch = |x: &Player, y: &Player| {
+                    played.contains(&(x.get_id().as_str(), y.get_id().as_str()))
+                };
+
+                // prefer opponents from the same score group,
+                // floating down into the closest group (and pulling players back up) as needed,
+                // with one long float costing more than several short ones
+                let cost = |x: usize, y: usize| {
+                    self.get_score(&ordered[x])
+                        .abs_diff(self.get_score(&ordered[y]))
+                        .pow(2)
+                };
+
+                // never rematch players if at all possible
+                let paired = pair_players_with_fallback(
+                    ordered.len(),
+                    |x, y| {
+                        if rematch(&ordered[x], &ordered[y]) {
+                            None
+                        } else {
+                            Some(cost(x, y))
+                        }
+                    },
+                    |x, y| {
+                        if rematch(&ordered[x], &ordered[y]) {
+                            Some(REMATCH_COST + cost(x, y))
+                        } else {
+                            Some(cost(x, y))
+                        }
+                    },
+                )?;
+
+                for (x, y) in paired {
+                    pairings.push(Pairing::new(ordered[x].clone(), ordered[y].clone()));
+                }
+            }
+            Self::SingleSwiss(stats) => {
+                let default = SingleSwissStats::default();
+                let ordered = ordered
+                    .into_iter()
+                    .map(|player| {
+                        let stats = stats.get(player.get_id()).unwrap_or(&default);
+                        (player, stats)
+                    })
+                    .collect::<Vec<_>>();
+
+                let cost = |x: usize, y: usize, strict: bool| {
+                    let (x, xs) = &ordered[x];
+                    let (y, ys) = &ordered[y];
+                    single_swiss_cost(
+                        (x.get_id().as_str(), *xs),
+                        (y.get_id().as_str(), *ys),
+                        strict,
+                    )
+                };
+
+                let paired = pair_players_with_fallback(
+                    ordered.len(),
+                    |x, y| cost(x, y, true).map(|(cost, _)| cost),
+                    |x, y| cost(x, y, false).map(|(cost, _)| cost),
+                )?;
+
+                for (x, y) in paired {
+                    let side = cost(x, y, false)
+                        .and_then(|(_, side)| side)
+                        .unwrap_or_else(|| if rng.gen() { Side::Corp } else { Side::Runner });
+
+                    let (x, y) = (ordered[x].0.clone(), ordered[y].0.clone());
+                    pairings.push(match side {
+                        Side::Corp => Pairing::new_single_sided(x, y),
+                        Side::Runner => Pairing::new_single_sided(y, x),
+                    });
                 }
             }
-            Self::SingleSwiss(_) => todo!(),
         }
 
-        pairings
+        Ok(pairings)
     }
 
-    fn round_ended<'a>(&mut self, _results: impl AsRef<[(&'a Pairing, Result)]>) {
+    fn get_games_per_round(&self) -> usize {
         match self {
-            Self::Swiss(_) => {
-                // TODO: ensure the results make sense (each player has 2 games and the pairing results make sense)
+            Self::Swiss(_) => SWISS_GAMES_PER_ROUND,
+            Self::SingleSwiss(_) => SINGLE_SWISS_GAMES_PER_ROUND,
+        }
+    }
+
+    fn validate_results<'a>(
+        &self,
+        round: &Round,
+        results: impl AsRef<[(&'a Pairing, Result)]>,
+    ) -> std::result::Result<(), ResultsError> {
+        validate_results(round, results.as_ref(), self.get_games_per_round())
+    }
+
+    fn round_ended<'a>(
+        &mut self,
+        round: &Round,
+        results: impl AsRef<[(&'a Pairing, Result)]>,
+        scoring: &ScoringSystem,
+    ) {
+        let results = results.as_ref();
+
+        match self {
+            Self::Swiss(stats) => {
+                for pairing in round.get_pairings() {
+                    let player = stats
+                        .entry(pairing.get_player().get_id().clone())
+                        .or_default();
+                    player.rounds_played += 1;
+
+                    if let Some(opponent) = pairing.get_opponent() {
+                        player.opponents.push(opponent.get_id().clone());
+
+                        let opponent = stats.entry(opponent.get_id().clone()).or_default();
+                        opponent.rounds_played += 1;
+                        opponent
+                            .opponents
+                            .push(pairing.get_player().get_id().clone());
+                    } else {
+                        player.byes += 1;
+                    }
+                }
+
+                record_results(stats, results, scoring);
+
+                SwissStats::update_tiebreakers(stats);
+            }
+            Self::SingleSwiss(stats) => {
+                for pairing in round.get_pairings() {
+                    let player = stats
+                        .entry(pairing.get_player().get_id().clone())
+                        .or_default();
+                    player.swiss.rounds_played += 1;
+
+                    if let Some(opponent) = pairing.get_opponent() {
+                        let side = pairing.get_player_side(pairing.get_player()).unwrap();
+                        player.played(opponent.get_id(), side);
+
+                        let opponent = stats.entry(opponent.get_id().clone()).or_default();
+                        opponent.swiss.rounds_played += 1;
+                        opponent.played(pairing.get_player().get_id(), side.opposite());
+                    } else {
+                        player.swiss.byes += 1;
+                    }
+                }
 
-                // TODO: update each player's stats
+                record_results(stats, results, scoring);
 
-                todo!()
+                SwissStats::update_tiebreakers(stats);
             }
-            Self::SingleSwiss(_) => todo!(),
         }
     }
 }
 
+/// How players who join after the first round are scored for the rounds they missed
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LateJoinScoring {
+    /// Missed rounds count as losses, worth the scoring system's points for a loss
+    #[default]
+    Loss,
+
+    /// Missed rounds count as byes, worth the scoring system's points for a bye
+    ///
+    /// These don't count towards the player's byes when assigning byes
+    Bye,
+}
+
+/// Current version of the serialized tournament state
+///
+/// Bump this whenever the format changes in a way that older versions can't load,
+/// making sure that files saved by older versions still load
+/// (new fields should have serde defaults)
+pub const FORMAT_VERSION: u32 = 6;
+
+/// Ensures the saved format version can be loaded by this version of the crate
+///
+/// Any version up to the current one is accepted and kept as it was saved,
+/// so that fields whose layout changed can tell which layout they were saved in
+fn deserialize_format_version<'de, D>(deserializer: D) -> std::result::Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let version = u32::deserialize(deserializer)?;
+    if version > FORMAT_VERSION {
+        return Err(de::Error::custom(format!(
+            "unsupported format version {}, expected {} or older",
+            version, FORMAT_VERSION
+        )));
+    }
+    Ok(version)
+}
+
+/// Saves the current format version, since the state is always saved in the current format
+fn serialize_format_version<S>(_: &u32, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_u32(FORMAT_VERSION)
+}
+
 /// Pairings
-#[derive(Debug)]
+///
+/// The whole tournament state can be saved and restored with serde
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pairings {
+    // the version the state was loaded from
+    #[serde(
+        serialize_with = "serialize_format_version",
+        deserialize_with = "deserialize_format_version"
+    )]
+    version: u32,
+
     algorithm: PairingsAlgorithm,
     rounds: Vec<Round>,
+
+    // every registered player, in registration order
+    players: Vec<Player>,
+
+    // dropped player ids and the number of rounds that had started when they dropped
+    dropped: HashMap<String, usize>,
+
+    // decklists registered by each player, by player id
+    #[serde(default)]
+    decklists: HashMap<String, PlayerDecklists>,
+
+    late_join_scoring: LateJoinScoring,
+
+    #[serde(default)]
+    elimination_format: EliminationFormat,
+
+    #[serde(default)]
+    scoring: ScoringSystem,
+
+    // every random decision is derived from this
+    // so that the tournament can be replayed exactly
+    seed: u64,
+
+    // top cut, once the Swiss rounds are over
+    #[serde(default)]
+    elimination: Option<Elimination>,
 }
 
 impl Pairings {
@@ -265,8 +881,17 @@ impl Pairings {
     #[inline]
     pub fn new_swiss() -> Self {
         Self {
+            version: FORMAT_VERSION,
             algorithm: PairingsAlgorithm::new_swiss(),
             rounds: vec![],
+            players: vec![],
+            dropped: HashMap::new(),
+            decklists: HashMap::new(),
+            late_join_scoring: LateJoinScoring::default(),
+            elimination_format: EliminationFormat::default(),
+            scoring: ScoringSystem::default(),
+            seed: thread_rng().gen(),
+            elimination: None,
         }
     }
 
@@ -274,149 +899,741 @@ impl Pairings {
     #[inline]
     pub fn new_single_swiss() -> Self {
         Self {
+            version: FORMAT_VERSION,
             algorithm: PairingsAlgorithm::new_single_swiss(),
             rounds: vec![],
+            players: vec![],
+            dropped: HashMap::new(),
+            decklists: HashMap::new(),
+            late_join_scoring: LateJoinScoring::default(),
+            elimination_format: EliminationFormat::default(),
+            scoring: ScoringSystem::default(),
+            seed: thread_rng().gen(),
+            elimination: None,
         }
     }
 
+    /// Gets the format version the tournament state was loaded from
+    ///
+    /// This is the current version for new tournaments
+    #[inline]
+    pub fn get_format_version(&self) -> u32 {
+        self.version
+    }
+
+    /// Sets the seed used for all random pairing decisions and tiebreakers
+    ///
+    /// The same seed, players, and results will always produce the same pairings
+    #[inline]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Seeds all random pairing decisions and tiebreakers from the given RNG
+    #[inline]
+    pub fn with_rng(self, rng: &mut impl RngCore) -> Self {
+        self.with_seed(rng.next_u64())
+    }
+
+    /// Sets how players who join after the first round are scored for the rounds they missed
+    #[inline]
+    pub fn with_late_join_scoring(mut self, late_join_scoring: LateJoinScoring) -> Self {
+        self.late_join_scoring = late_join_scoring;
+        self
+    }
+
+    /// Sets the format of the top cut
+    #[inline]
+    pub fn with_elimination_format(mut self, elimination_format: EliminationFormat) -> Self {
+        self.elimination_format = elimination_format;
+        self
+    }
+
+    /// Sets the points awarded for each game result in Swiss rounds
+    #[inline]
+    pub fn with_scoring_system(mut self, scoring: ScoringSystem) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Gets the points awarded for each game result in Swiss rounds
+    #[inline]
+    pub fn get_scoring_system(&self) -> &ScoringSystem {
+        &self.scoring
+    }
+
+    /// Gets the seed used for all random pairing decisions and tiebreakers
+    #[inline]
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Gets the RNG for the given player registration
+    ///
+    /// Registrations use their own stream, separate from every round
+    #[inline]
+    fn registration_rng(&self, registration: usize) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        rng.set_stream(u64::MAX);
+        // each draw is a u64, or 2 words
+        rng.set_word_pos(registration as u128 * 2);
+        rng
+    }
+
+    /// Gets the RNG for the given round
+    ///
+    /// Each round has its own stream so that replaying a round doesn't depend on earlier rounds
+    #[inline]
+    fn round_rng(&self, round: usize) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        rng.set_stream(round as u64);
+        rng
+    }
+
     /// Gets the number of rounds needed for the given player count
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if player_count is less than 2
+    /// Returns an error if player_count is less than 2
     #[inline]
-    pub fn get_total_rounds(&self, player_count: usize) -> usize {
+    pub fn get_total_rounds(
+        &self,
+        player_count: usize,
+    ) -> std::result::Result<usize, PairingsError> {
         self.algorithm.get_total_rounds(player_count)
     }
 
     /// Gets the top cut number of players given the player count
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if player_count is less than 2
     #[inline]
-    pub fn get_top_cut(&self, player_count: usize) -> Option<usize> {
+    pub fn get_top_cut(
+        &self,
+        player_count: usize,
+    ) -> std::result::Result<Option<usize>, PairingsError> {
         self.algorithm.get_top_cut(player_count)
     }
 
+    /// Checks if this is a single-sided tournament
+    #[inline]
+    pub fn is_single_sided(&self) -> bool {
+        matches!(self.algorithm, PairingsAlgorithm::SingleSwiss(_))
+    }
+
+    /// Gets every round so far, including the current one
+    #[inline]
+    pub fn get_rounds(&self) -> &Vec<Round> {
+        &self.rounds
+    }
+
     /// Gets the current round number
     #[inline]
     pub fn get_current_round(&self) -> usize {
         self.rounds.len() + 1
     }
 
-    /// Determine the next pairing of the given players
-    pub fn next_round(&mut self, players: impl AsRef<[Player]>) -> Vec<Pairing> {
-        let players = players.as_ref().to_owned();
-        let pairings = self.algorithm.next_pairings(players, &self.rounds);
-
-        self.rounds.push(Round::new(pairings.clone()));
-
-        pairings
+    /// Gets every player who has received a bye, in the order they received them
+    ///
+    /// Players with more than one bye are only included once
+    pub fn players_with_byes(&self) -> Vec<&Player> {
+        let mut players: Vec<&Player> = vec![];
+        for round in &self.rounds {
+            for pairing in round.get_pairings() {
+                if pairing.get_opponent().is_none() && !players.contains(&pairing.get_player()) {
+                    players.push(pairing.get_player());
+                }
+            }
+        }
+        players
     }
 
-    /// Update internal state with round results
-    pub fn round_ended<'a>(&mut self, results: impl AsRef<[(&'a Pairing, Result)]>) {
-        self.rounds.last_mut().unwrap().round_ended(&results);
-
-        self.algorithm.round_ended(results)
+    /// Gets every registered player, including those who have dropped
+    #[inline]
+    pub fn get_players(&self) -> &Vec<Player> {
+        &self.players
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Gets the registered player with the given id
+    #[inline]
+    pub fn get_player(&self, id: impl AsRef<str>) -> Option<&Player> {
+        self.players
+            .iter()
+            .find(|player| player.get_id() == id.as_ref())
+    }
 
-    #[test]
-    #[should_panic]
-    fn invalid_round_count_0_players() {
-        let pairings = Pairings::new_swiss();
+    /// Registers a player's decklist for the given side, replacing any earlier one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not part of the tournament
+    pub fn set_decklist(
+        &mut self,
+        player: &Player,
+        side: Side,
+        decklist: Decklist,
+    ) -> std::result::Result<(), PairingsError> {
+        if !self.players.contains(player) {
+            return Err(PairingsError::UnknownPlayer(player.get_id().clone()));
+        }
 
-        pairings.get_total_rounds(0);
+        self.decklists
+            .entry(player.get_id().clone())
+            .or_default()
+            .set(side, decklist);
+        Ok(())
     }
 
-    #[test]
-    #[should_panic]
-    fn invalid_round_count_1_player() {
-        let pairings = Pairings::new_swiss();
+    /// Gets the decklist the player registered for the given side
+    #[inline]
+    pub fn get_decklist(&self, player: &Player, side: Side) -> Option<&Decklist> {
+        self.decklists.get(player.get_id())?.get(side)
+    }
 
-        pairings.get_total_rounds(1);
+    /// Checks every registered decklist against the rules of a format
+    ///
+    /// Decklists without an identity use the one the player registered for that side
+    pub fn check_decklists(&self, database: &CardDatabase, format: Format) -> Vec<DeckViolation> {
+        let mut violations = vec![];
+        for player in &self.players {
+            for side in [Side::Corp, Side::Runner] {
+                let Some(decklist) = self.get_decklist(player, side) else {
+                    continue;
+                };
+                let decklist = match player.get_identity(side) {
+                    Some(identity) if decklist.get_identity().is_none() => {
+                        Cow::Owned(Decklist::new(
+                            Some(identity.get_name().clone()),
+                            decklist.get_cards().clone(),
+                        ))
+                    }
+                    _ => Cow::Borrowed(decklist),
+                };
+
+                violations.extend(
+                    check_decklist(database, &decklist, side, format)
+                        .into_iter()
+                        .map(|violation| {
+                            DeckViolation::new(player.get_id().clone(), side, violation)
+                        }),
+                );
+            }
+        }
+        violations
     }
 
-    #[test]
-    fn round_counts() {
-        let pairings = Pairings::new_swiss();
+    /// Gets the players who will be paired in the next round
+    pub fn get_active_players(&self) -> Vec<&Player> {
+        self.players
+            .iter()
+            .filter(|player| !self.is_dropped(player))
+            .collect()
+    }
 
-        assert_eq!(pairings.get_total_rounds(2), 3);
-        assert_eq!(pairings.get_total_rounds(9), 3);
+    /// Checks if the given player has dropped from the tournament
+    #[inline]
+    pub fn is_dropped(&self, player: &Player) -> bool {
+        self.dropped.contains_key(player.get_id())
+    }
 
-        assert_eq!(pairings.get_total_rounds(10), 4);
-        assert_eq!(pairings.get_total_rounds(15), 4);
-        assert_eq!(pairings.get_total_rounds(16), 4);
-        assert_eq!(pairings.get_total_rounds(24), 4);
-        assert_eq!(pairings.get_total_rounds(25), 4);
-        assert_eq!(pairings.get_total_rounds(32), 4);
+    fn add_player(&mut self, player: Player) -> &Player {
+        let tiebreak = self.registration_rng(self.players.len()).gen();
+        self.algorithm.add_player(&player, tiebreak);
 
-        assert_eq!(pairings.get_total_rounds(33), 5);
-        assert_eq!(pairings.get_total_rounds(56), 5);
+        self.players.push(player);
+        self.players.last().unwrap()
+    }
 
-        assert_eq!(pairings.get_total_rounds(57), 6);
-        assert_eq!(pairings.get_total_rounds(80), 6);
+    /// Registers a player before the first round
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is already registered or the first round has started,
+    /// in which case they need to join late
+    pub fn register_player(&mut self, player: Player) -> std::result::Result<(), PairingsError> {
+        if self.get_player(player.get_id()).is_some() {
+            return Err(PairingsError::DuplicatePlayer(player.get_id().clone()));
+        }
 
-        assert_eq!(pairings.get_total_rounds(81), 7);
-        assert_eq!(pairings.get_total_rounds(128), 7);
-        assert_eq!(pairings.get_total_rounds(129), 7);
-        assert_eq!(pairings.get_total_rounds(192), 7);
+        if !self.rounds.is_empty() {
+            return Err(PairingsError::TournamentStarted);
+        }
 
-        assert_eq!(pairings.get_total_rounds(193), 8);
-        assert_eq!(pairings.get_total_rounds(256), 8);
+        self.add_player(player);
 
-        assert_eq!(pairings.get_total_rounds(257), 9);
-        assert_eq!(pairings.get_total_rounds(usize::MAX), 9);
+        Ok(())
     }
 
-    #[test]
-    #[should_panic]
-    fn invalid_top_cut_0_players() {
-        let pairings = Pairings::new_swiss();
+    /// Adds a player after the first round has started, or re-enters a dropped player
+    ///
+    /// Rounds the player missed are scored according to the late join scoring
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is already registered and hasn't dropped
+    pub fn late_join(&mut self, player: Player) -> std::result::Result<(), PairingsError> {
+        let started = self.rounds.len();
+
+        let (player, missed) = match self.dropped.remove(player.get_id()) {
+            Some(dropped) => (player, started - dropped),
+            None => {
+                if self.get_player(player.get_id()).is_some() {
+                    return Err(PairingsError::DuplicatePlayer(player.get_id().clone()));
+                }
 
-        pairings.get_top_cut(0);
-    }
+                (self.add_player(player).clone(), started)
+            }
+        };
 
-    #[test]
-    #[should_panic]
-    fn invalid_top_cut_1_player() {
-        let pairings = Pairings::new_swiss();
+        self.algorithm.missed_rounds(
+            &player,
+            missed as u64,
+            self.late_join_scoring,
+            &self.scoring,
+        );
 
-        pairings.get_top_cut(1);
+        Ok(())
     }
 
-    #[test]
-    fn top_cut() {
-        let pairings = Pairings::new_swiss();
-
-        assert_eq!(pairings.get_top_cut(2), None);
-        assert_eq!(pairings.get_top_cut(9), None);
-        assert_eq!(pairings.get_top_cut(10), None);
-        assert_eq!(pairings.get_top_cut(15), None);
+    /// Drops a player from the tournament
+    ///
+    /// Dropped players are no longer paired but remain in the standings
+    /// and still count towards their opponents' tiebreakers.
+    /// A player dropping during a round still has that round's results reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the player is not registered or has already dropped
+    pub fn drop_player(&mut self, player: &Player) -> std::result::Result<(), PairingsError> {
+        if self.get_player(player.get_id()).is_none() {
+            return Err(PairingsError::UnknownPlayer(player.get_id().clone()));
+        }
 
-        assert_eq!(pairings.get_top_cut(16), Some(4));
-        assert_eq!(pairings.get_top_cut(24), Some(4));
+        if self.is_dropped(player) {
+            return Err(PairingsError::PlayerDropped(player.get_id().clone()));
+        }
 
-        assert_eq!(pairings.get_top_cut(25), Some(8));
-        assert_eq!(pairings.get_top_cut(32), Some(8));
-        assert_eq!(pairings.get_top_cut(33), Some(8));
-        assert_eq!(pairings.get_top_cut(56), Some(8));
-        assert_eq!(pairings.get_top_cut(57), Some(8));
-        assert_eq!(pairings.get_top_cut(80), Some(8));
-        assert_eq!(pairings.get_top_cut(81), Some(8));
-        assert_eq!(pairings.get_top_cut(128), Some(8));
+        self.dropped
+            .insert(player.get_id().clone(), self.rounds.len());
 
-        assert_eq!(pairings.get_top_cut(129), Some(16));
-        assert_eq!(pairings.get_top_cut(192), Some(16));
-        assert_eq!(pairings.get_top_cut(193), Some(16));
-        assert_eq!(pairings.get_top_cut(256), Some(16));
-        assert_eq!(pairings.get_top_cut(257), Some(16));
-        assert_eq!(pairings.get_top_cut(usize::MAX), Some(16));
+        Ok(())
     }
 
-    #[test]
+    /// Gets the current standings, highest ranked first
+    ///
+    /// Dropped players remain in the standings but are never placed in the top cut
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than 2 players are registered
+    pub fn standings(&self) -> std::result::Result<Vec<Standing>, PairingsError> {
+        let top_cut = self.get_top_cut(self.players.len())?.unwrap_or(0);
+
+        let mut players = self.players.clone();
+        self.algorithm.rank_players(&mut players)?;
+
+        let mut cut = 0;
+        let standings = players
+            .into_iter()
+            .enumerate()
+            .map(|(idx, player)| {
+                let stats = self.algorithm.get_stats(&player).unwrap();
+
+                let top_cut = !self.is_dropped(&player) && cut < top_cut;
+                if top_cut {
+                    cut += 1;
+                }
+
+                Standing::new(
+                    idx + 1,
+                    player,
+                    stats.score,
+                    stats.sos,
+                    stats.esos,
+                    stats.record,
+                    stats.corp_record,
+                    stats.runner_record,
+                    top_cut,
+                )
+            })
+            .collect();
+
+        Ok(standings)
+    }
+
+    /// Seeds the top cut from the current standings and starts the elimination rounds
+    ///
+    /// Swiss rounds can no longer be paired once the top cut has started
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current round has not ended,
+    /// the tournament is too small to have a top cut,
+    /// or the top cut has already started
+    pub fn start_top_cut(&mut self) -> std::result::Result<&mut Elimination, PairingsError> {
+        if self.elimination.is_some() {
+            return Err(PairingsError::TopCutStarted);
+        }
+
+        if let Some(round) = self.rounds.last() {
+            if !round.has_ended() {
+                return Err(PairingsError::RoundInProgress);
+            }
+        }
+
+        if self.get_top_cut(self.players.len())?.is_none() {
+            return Err(PairingsError::NoTopCut);
+        }
+
+        let seeds = self
+            .standings()?
+            .into_iter()
+            .filter(Standing::is_top_cut)
+            .map(|standing| standing.get_player().clone())
+            .collect();
+
+        Ok(self
+            .elimination
+            .insert(Elimination::new(seeds, self.elimination_format)?))
+    }
+
+    /// Starts a top cut that was seeded outside of this tournament, such as an imported one
+    ///
+    /// Seeds are the top cut players, highest seed first
+    pub(crate) fn import_top_cut(
+        &mut self,
+        seeds: Vec<Player>,
+        format: EliminationFormat,
+    ) -> std::result::Result<&mut Elimination, PairingsError> {
+        if self.elimination.is_some() {
+            return Err(PairingsError::TopCutStarted);
+        }
+
+        if let Some(round) = self.rounds.last() {
+            if !round.has_ended() {
+                return Err(PairingsError::RoundInProgress);
+            }
+        }
+
+        if let Some(player) = seeds
+            .iter()
+            .find(|player| self.get_player(player.get_id()).is_none())
+        {
+            return Err(PairingsError::UnknownPlayer(player.get_id().clone()));
+        }
+
+        self.elimination_format = format;
+        Ok(self.elimination.insert(Elimination::new(seeds, format)?))
+    }
+
+    /// Gets the top cut, if it has started
+    #[inline]
+    pub fn get_elimination(&self) -> Option<&Elimination> {
+        self.elimination.as_ref()
+    }
+
+    /// Gets the top cut for pairing and reporting elimination rounds, if it has started
+    #[inline]
+    pub fn get_elimination_mut(&mut self) -> Option<&mut Elimination> {
+        self.elimination.as_mut()
+    }
+
+    /// Determine the next pairing of the active players
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are fewer than 2 active players,
+    /// the current round has not ended, or the top cut has started
+    pub fn next_round(&mut self) -> std::result::Result<Vec<Pairing>, PairingsError> {
+        if self.elimination.is_some() {
+            return Err(PairingsError::TopCutStarted);
+        }
+
+        let players = self
+            .get_active_players()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        if players.len() < 2 {
+            return Err(PairingsError::NotEnoughPlayers(players.len()));
+        }
+
+        if let Some(round) = self.rounds.last() {
+            if !round.has_ended() {
+                return Err(PairingsError::RoundInProgress);
+            }
+        }
+
+        let mut rng = self.round_rng(self.rounds.len());
+        let pairings = self
+            .algorithm
+            .next_pairings(players, &self.rounds, &mut rng)?;
+
+        self.rounds.push(Round::new(pairings.clone()));
+
+        Ok(pairings)
+    }
+
+    /// Adds a round that was paired outside of this tournament, such as an imported one
+    ///
+    /// Results are (pairing index, result) in the same form as round_ended,
+    /// or None if the round is still in progress
+    pub(crate) fn import_round(
+        &mut self,
+        pairings: Vec<Pairing>,
+        results: Option<Vec<(usize, Result)>>,
+    ) -> std::result::Result<(), PairingsError> {
+        if let Some(round) = self.rounds.last() {
+            if !round.has_ended() {
+                return Err(PairingsError::RoundInProgress);
+            }
+        }
+
+        for pairing in &pairings {
+            for player in [Some(pairing.get_player()), pairing.get_opponent().as_ref()]
+                .into_iter()
+                .flatten()
+            {
+                if self.get_player(player.get_id()).is_none() {
+                    return Err(PairingsError::UnknownPlayer(player.get_id().clone()));
+                }
+            }
+        }
+
+        self.rounds.push(Round::new(pairings.clone()));
+
+        if let Some(results) = results {
+            let results = results
+                .into_iter()
+                .map(|(idx, result)| (&pairings[idx], result))
+                .collect::<Vec<_>>();
+            self.round_ended(results)?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates empty player reports for the current round
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no round in progress
+    pub fn new_round_reports(&self) -> std::result::Result<RoundReports, PairingsError> {
+        let round = self.rounds.last().ok_or(PairingsError::RoundNotStarted)?;
+        if round.has_ended() {
+            return Err(PairingsError::RoundAlreadyEnded);
+        }
+
+        Ok(RoundReports::new(
+            self.rounds.len(),
+            round,
+            self.algorithm.get_games_per_round(),
+        ))
+    }
+
+    /// Update internal state with round results
+    ///
+    /// Each result is a single game from the point of view of the pairing's first player.
+    /// Double-sided pairings report the game where the first player is the Corp first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no round in progress,
+    /// or if the results don't cover every pairing in the round,
+    /// have the wrong number of games for a pairing, or misreport a bye
+    pub fn round_ended<'a>(
+        &mut self,
+        results: impl AsRef<[(&'a Pairing, Result)]>,
+    ) -> std::result::Result<(), PairingsError> {
+        let round = self
+            .rounds
+            .last_mut()
+            .ok_or(PairingsError::RoundNotStarted)?;
+        if round.has_ended() {
+            return Err(PairingsError::RoundAlreadyEnded);
+        }
+
+        self.algorithm.validate_results(round, &results)?;
+
+        round.round_ended(&results);
+        self.algorithm.round_ended(round, results, &self.scoring);
+
+        Ok(())
+    }
+
+    /// Ends the current round with the results confirmed by player reports
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reports are for another round,
+    /// if any pairing's results are still pending or disputed,
+    /// or if the confirmed results are invalid for the round
+    pub fn round_ended_from_reports(
+        &mut self,
+        reports: &RoundReports,
+    ) -> std::result::Result<(), PairingsError> {
+        let round = self.rounds.last().ok_or(PairingsError::RoundNotStarted)?;
+        if reports.get_round() != self.rounds.len() {
+            return Err(PairingsError::WrongRoundReports);
+        }
+        let results = reports
+            .get_confirmed_results(round)?
+            .into_iter()
+            .map(|(pairing, result)| (pairing.clone(), result))
+            .collect::<Vec<_>>();
+
+        self.round_ended(
+            results
+                .iter()
+                .map(|(pairing, result)| (pairing, *result))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swiss_stats<'a>(pairings: &'a Pairings, player: &Player) -> &'a SwissStats {
+        match &pairings.algorithm {
+            PairingsAlgorithm::Swiss(stats) => stats.get(player.get_id()).unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn register_players(pairings: &mut Pairings, players: &[Player]) {
+        for player in players {
+            pairings.register_player(player.clone()).unwrap();
+        }
+    }
+
+    fn draw_round(pairings: &mut Pairings, round: &[Pairing]) {
+        let results = round
+            .iter()
+            .flat_map(|pairing| {
+                let result = if pairing.get_opponent().is_some() {
+                    Result::Draw
+                } else {
+                    Result::Bye
+                };
+                [(pairing, result), (pairing, result)]
+            })
+            .collect::<Vec<_>>();
+        pairings.round_ended(results).unwrap();
+    }
+
+    fn single_swiss_stats<'a>(pairings: &'a Pairings, player: &Player) -> &'a SingleSwissStats {
+        match &pairings.algorithm {
+            PairingsAlgorithm::SingleSwiss(stats) => stats.get(player.get_id()).unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn invalid_round_count_0_players() {
+        let pairings = Pairings::new_swiss();
+
+        assert_eq!(
+            pairings.get_total_rounds(0),
+            Err(PairingsError::NotEnoughPlayers(0))
+        );
+    }
+
+    #[test]
+    fn invalid_round_count_1_player() {
+        let pairings = Pairings::new_swiss();
+
+        assert_eq!(
+            pairings.get_total_rounds(1),
+            Err(PairingsError::NotEnoughPlayers(1))
+        );
+    }
+
+    #[test]
+    fn round_counts() {
+        let pairings = Pairings::new_swiss();
+
+        assert_eq!(pairings.get_total_rounds(2), Ok(3));
+        assert_eq!(pairings.get_total_rounds(9), Ok(3));
+
+        assert_eq!(pairings.get_total_rounds(10), Ok(4));
+        assert_eq!(pairings.get_total_rounds(15), Ok(4));
+        assert_eq!(pairings.get_total_rounds(16), Ok(4));
+        assert_eq!(pairings.get_total_rounds(24), Ok(4));
+        assert_eq!(pairings.get_total_rounds(25), Ok(4));
+        assert_eq!(pairings.get_total_rounds(32), Ok(4));
+
+        assert_eq!(pairings.get_total_rounds(33), Ok(5));
+        assert_eq!(pairings.get_total_rounds(56), Ok(5));
+
+        assert_eq!(pairings.get_total_rounds(57), Ok(6));
+        assert_eq!(pairings.get_total_rounds(80), Ok(6));
+
+        assert_eq!(pairings.get_total_rounds(81), Ok(7));
+        assert_eq!(pairings.get_total_rounds(128), Ok(7));
+        assert_eq!(pairings.get_total_rounds(129), Ok(7));
+        assert_eq!(pairings.get_total_rounds(192), Ok(7));
+
+        assert_eq!(pairings.get_total_rounds(193), Ok(8));
+        assert_eq!(pairings.get_total_rounds(256), Ok(8));
+
+        assert_eq!(pairings.get_total_rounds(257), Ok(9));
+        assert_eq!(pairings.get_total_rounds(usize::MAX), Ok(9));
+    }
+
+    #[test]
+    fn invalid_top_cut_0_players() {
+        let pairings = Pairings::new_swiss();
+
+        assert_eq!(
+            pairings.get_top_cut(0),
+            Err(PairingsError::NotEnoughPlayers(0))
+        );
+    }
+
+    #[test]
+    fn invalid_top_cut_1_player() {
+        let pairings = Pairings::new_swiss();
+
+        assert_eq!(
+            pairings.get_top_cut(1),
+            Err(PairingsError::NotEnoughPlayers(1))
+        );
+    }
+
+    #[test]
+    fn top_cut() {
+        let pairings = Pairings::new_swiss();
+
+        assert_eq!(pairings.get_top_cut(2), Ok(None));
+        assert_eq!(pairings.get_top_cut(9), Ok(None));
+        assert_eq!(pairings.get_top_cut(10), Ok(None));
+        assert_eq!(pairings.get_top_cut(15), Ok(None));
+
+        assert_eq!(pairings.get_top_cut(16), Ok(Some(4)));
+        assert_eq!(pairings.get_top_cut(24), Ok(Some(4)));
+
+        assert_eq!(pairings.get_top_cut(25), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(32), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(33), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(56), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(57), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(80), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(81), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(128), Ok(Some(8)));
+
+        assert_eq!(pairings.get_top_cut(129), Ok(Some(16)));
+        assert_eq!(pairings.get_top_cut(192), Ok(Some(16)));
+        assert_eq!(pairings.get_top_cut(193), Ok(Some(16)));
+        assert_eq!(pairings.get_top_cut(256), Ok(Some(16)));
+        assert_eq!(pairings.get_top_cut(257), Ok(Some(16)));
+        assert_eq!(pairings.get_top_cut(usize::MAX), Ok(Some(16)));
+    }
+
+    #[test]
     fn two_players_first_round() {
         let players = vec![
             Player::new("afirst", "alast", None),
@@ -424,8 +1641,9 @@ mod tests {
         ];
 
         let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
 
-        let first_round = pairings.next_round(&players);
+        let first_round = pairings.next_round().unwrap();
         assert_eq!(first_round.len(), 1);
         assert!(
             first_round[0].get_player().get_full_name() == players[0].get_full_name()
@@ -437,14 +1655,22 @@ mod tests {
                     == players[0].get_full_name()
         );
 
-        pairings.round_ended(vec![
-            // game 1
-            (&first_round[0], Result::Win),
-            // game 2
-            (&first_round[0], Result::Draw),
-        ]);
-
-        // TODO: validate the updated results
+        pairings
+            .round_ended(vec![
+                // game 1
+                (&first_round[0], Result::Win),
+                // game 2
+                (&first_round[0], Result::Draw),
+            ])
+            .unwrap();
+
+        let player = swiss_stats(&pairings, first_round[0].get_player());
+        assert_eq!(player.score, 4);
+        assert_eq!(player.rounds_played, 1);
+
+        let opponent = swiss_stats(&pairings, first_round[0].get_opponent().as_ref().unwrap());
+        assert_eq!(opponent.score, 1);
+        assert_eq!(opponent.rounds_played, 1);
     }
 
     #[test]
@@ -456,20 +1682,1110 @@ mod tests {
         ];
 
         let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
 
-        let first_round = pairings.next_round(&players);
+        let first_round = pairings.next_round().unwrap();
         assert_eq!(first_round.len(), 2);
         assert!(first_round[0].get_opponent().is_none());
 
-        pairings.round_ended(vec![
-            // game 1
-            (&first_round[0], Result::Win),
-            (&first_round[0], Result::Bye),
-            // game 2
-            (&first_round[0], Result::Draw),
-            (&first_round[0], Result::Bye),
-        ]);
+        pairings
+            .round_ended(vec![
+                // game 1
+                (&first_round[1], Result::Win),
+                (&first_round[0], Result::Bye),
+                // game 2
+                (&first_round[1], Result::Draw),
+                (&first_round[0], Result::Bye),
+            ])
+            .unwrap();
+
+        let bye = swiss_stats(&pairings, first_round[0].get_player());
+        assert_eq!(bye.score, 6);
+        assert_eq!(bye.rounds_played, 1);
+
+        let player = swiss_stats(&pairings, first_round[1].get_player());
+        assert_eq!(player.score, 4);
+        assert_eq!(player.rounds_played, 1);
+
+        let opponent = swiss_stats(&pairings, first_round[1].get_opponent().as_ref().unwrap());
+        assert_eq!(opponent.score, 1);
+        assert_eq!(opponent.rounds_played, 1);
+    }
+
+    #[test]
+    fn invalid_results() {
+        let players = vec![
+            Player::new("afirst", "alast", None),
+            Player::new("bfirst", "blast", None),
+            Player::new("cfirst", "clast", None),
+        ];
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        let first_round = pairings.next_round().unwrap();
+        let bye = first_round[0].get_player().get_id().clone();
+        let player = first_round[1].get_player().get_id().clone();
+
+        // missing a game
+        assert_eq!(
+            pairings.round_ended(vec![
+                (&first_round[1], Result::Win),
+                (&first_round[0], Result::Bye),
+                (&first_round[0], Result::Bye),
+            ]),
+            Err(PairingsError::InvalidResults(ResultsError::GameCount {
+                player: player.clone(),
+                expected: 2,
+                actual: 1,
+            }))
+        );
+
+        // bye for a player with an opponent
+        assert_eq!(
+            pairings.round_ended(vec![
+                (&first_round[1], Result::Win),
+                (&first_round[1], Result::Bye),
+                (&first_round[0], Result::Bye),
+                (&first_round[0], Result::Bye),
+            ]),
+            Err(PairingsError::InvalidResults(ResultsError::InvalidBye(
+                player
+            )))
+        );
+
+        // game result for a bye
+        assert_eq!(
+            pairings.round_ended(vec![
+                (&first_round[1], Result::Win),
+                (&first_round[1], Result::Loss),
+                (&first_round[0], Result::Win),
+                (&first_round[0], Result::Bye),
+            ]),
+            Err(PairingsError::InvalidResults(ResultsError::InvalidBye(bye)))
+        );
+
+        // pairing from somewhere else
+        let other = Pairing::new(players[0].clone(), Player::new("dfirst", "dlast", None));
+        assert!(matches!(
+            pairings.round_ended(vec![(&other, Result::Win), (&other, Result::Loss),]),
+            Err(PairingsError::InvalidResults(ResultsError::UnknownPairing(
+                _
+            )))
+        ));
+
+        // nothing should have been recorded
+        assert!(!pairings.rounds.last().unwrap().has_ended());
+        for player in &players {
+            let stats = swiss_stats(&pairings, player);
+            assert_eq!(stats.score, 0);
+            assert_eq!(stats.rounds_played, 0);
+        }
+    }
+
+    #[test]
+    fn scores_carry_over() {
+        let players = (0..4)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        for _ in 0..2 {
+            let round = pairings.next_round().unwrap();
+            let results = round
+                .iter()
+                .flat_map(|pairing| [(pairing, Result::Win), (pairing, Result::Win)])
+                .collect::<Vec<_>>();
+            pairings.round_ended(results).unwrap();
+        }
+
+        let mut scores = players
+            .iter()
+            .map(|player| swiss_stats(&pairings, player).score)
+            .collect::<Vec<_>>();
+        scores.sort();
+        assert_eq!(scores, vec![0, 6, 6, 12]);
+
+        for player in &players {
+            assert_eq!(swiss_stats(&pairings, player).rounds_played, 2);
+        }
+    }
+
+    #[test]
+    fn no_rematches() {
+        let players = (0..8)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        let mut played = HashSet::new();
+        for _ in 0..pairings.get_total_rounds(players.len()).unwrap() {
+            let round = pairings.next_round().unwrap();
+            assert_eq!(round.len(), 4);
+
+            for pairing in &round {
+                let player = pairing.get_player().get_id().clone();
+                let opponent = pairing.get_opponent().as_ref().unwrap().get_id().clone();
+                assert!(played.insert((player.clone(), opponent.clone())));
+                assert!(played.insert((opponent, player)));
+            }
+
+            draw_round(&mut pairings, &round);
+        }
+    }
+
+    #[test]
+    fn lowest_cost_pairing() {
+        // pairing the first two players is cheapest on its own,
+        // but forces an expensive pairing for the last two
+        let costs = [[0, 0, 1, 5], [0, 0, 5, 1], [1, 5, 0, 10], [5, 1, 10, 0]];
+        let paired = pair_players(4, |x, y| Some(costs[x][y])).unwrap();
+        assert_eq!(paired, Some(vec![(0, 2), (1, 3)]));
+
+        // the last player can't be paired with anyone
+        let paired = pair_players(4, |x, y| (y != 3).then_some(costs[x][y])).unwrap();
+        assert_eq!(paired, None);
+    }
+
+    #[test]
+    fn pairing_limit_fallback() {
+        // the last 3 players can only play each other, which takes too long to rule out
+        let count = 40;
+        let strict = |x: usize, y: usize| ((x < count - 3) == (y < count - 3)).then_some(0);
+        assert_eq!(
+            pair_players(count, strict),
+            Err(PairingsError::PairingLimitReached)
+        );
+
+        let paired = pair_players_with_fallback(count, strict, |_, _| Some(1)).unwrap();
+        assert_eq!(paired.len(), count / 2);
+    }
+
+    #[test]
+    fn forced_rematch() {
+        let players = vec![
+            Player::new("afirst", "alast", None),
+            Player::new("bfirst", "blast", None),
+        ];
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        let first_round = pairings.next_round().unwrap();
+        draw_round(&mut pairings, &first_round);
+
+        let second_round = pairings.next_round().unwrap();
+        assert_eq!(second_round.len(), 1);
+        assert_eq!(
+            first_round[0].get_player_opponent(&players[0]),
+            Some(&players[1])
+        );
+        assert_eq!(
+            second_round[0].get_player_opponent(&players[0]),
+            Some(&players[1])
+        );
+    }
+
+    #[test]
+    fn score_groups() {
+        let players = (0..4)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut stats = HashMap::new();
+        for (idx, player) in players.iter().enumerate() {
+            stats.insert(
+                player.get_id().clone(),
+                SwissStats {
+                    score: if idx < 2 { 6 } else { 0 },
+                    rounds_played: 1,
+                    ..Default::default()
+                },
+            );
+        }
+        let algorithm = PairingsAlgorithm::Swiss(stats);
+
+        let rounds = vec![Round::new(vec![
+            Pairing::new(players[0].clone(), players[2].clone()),
+            Pairing::new(players[1].clone(), players[3].clone()),
+        ])];
+
+        let pairings = algorithm
+            .next_pairings(players.clone(), &rounds, &mut thread_rng())
+            .unwrap();
+        assert_eq!(pairings.len(), 2);
+        assert_eq!(
+            pairings[0].get_player_opponent(&players[0]),
+            Some(&players[1])
+        );
+        assert_eq!(
+            pairings[1].get_player_opponent(&players[2]),
+            Some(&players[3])
+        );
+    }
+
+    #[test]
+    fn float_down() {
+        let players = (0..4)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut stats = HashMap::new();
+        for (idx, player) in players.iter().enumerate() {
+            stats.insert(
+                player.get_id().clone(),
+                SwissStats {
+                    score: if idx < 2 { 6 } else { 0 },
+                    rounds_played: 1,
+                    ..Default::default()
+                },
+            );
+        }
+        let algorithm = PairingsAlgorithm::Swiss(stats);
+
+        // the top two players have already played each other
+        let rounds = vec![Round::new(vec![
+            Pairing::new(players[0].clone(), players[1].clone()),
+            Pairing::new(players[2].clone(), players[3].clone()),
+        ])];
+
+        let pairings = algorithm
+            .next_pairings(players.clone(), &rounds, &mut thread_rng())
+            .unwrap();
+        assert_eq!(pairings.len(), 2);
+        for pairing in pairings {
+            let opponent = pairing.get_opponent().as_ref().unwrap();
+            assert!(!rounds[0].get_pairings().iter().any(|previous| {
+                previous.has_player(pairing.get_player()) && previous.has_player(opponent)
+            }));
+        }
+    }
+
+    #[test]
+    fn strength_of_schedule() {
+        let players = (0..5)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        let first_round = pairings.next_round().unwrap();
+        assert!(first_round[0].get_opponent().is_none());
+        pairings
+            .round_ended(vec![
+                (&first_round[0], Result::Bye),
+                (&first_round[0], Result::Bye),
+                (&first_round[1], Result::Win),
+                (&first_round[1], Result::Win),
+                (&first_round[2], Result::Draw),
+                (&first_round[2], Result::Draw),
+            ])
+            .unwrap();
+
+        let bye = swiss_stats(&pairings, first_round[0].get_player());
+        assert_eq!(bye.score, 6);
+        assert_eq!(bye.sos, 0.0);
+        assert_eq!(bye.esos, 0.0);
+
+        // winner played someone with 0 points, loser played someone with 6
+        let winner = swiss_stats(&pairings, first_round[1].get_player());
+        assert_eq!(winner.sos, 0.0);
+        assert_eq!(winner.esos, 6.0);
+
+        let loser = swiss_stats(&pairings, first_round[1].get_opponent().as_ref().unwrap());
+        assert_eq!(loser.sos, 6.0);
+        assert_eq!(loser.esos, 0.0);
+
+        let draw = swiss_stats(&pairings, first_round[2].get_player());
+        assert_eq!(draw.sos, 2.0);
+        assert_eq!(draw.esos, 2.0);
+    }
+
+    #[test]
+    fn tiebreaker_ranking() {
+        let players = (0..3)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut stats = HashMap::new();
+        for (idx, player) in players.iter().enumerate() {
+            stats.insert(
+                player.get_id().clone(),
+                SwissStats {
+                    score: 6,
+                    rounds_played: 2,
+                    sos: if idx == 0 { 1.0 } else { 2.0 },
+                    esos: idx as f32,
+                    ..Default::default()
+                },
+            );
+        }
+        let algorithm = PairingsAlgorithm::Swiss(stats);
+
+        let mut ranked = players.clone();
+        algorithm.rank_players(&mut ranked).unwrap();
+        assert_eq!(
+            ranked,
+            vec![players[2].clone(), players[1].clone(), players[0].clone()]
+        );
+    }
+
+    #[test]
+    fn single_swiss_round_counts() {
+        let pairings = Pairings::new_single_swiss();
+
+        assert_eq!(pairings.get_total_rounds(2), Ok(4));
+        assert_eq!(pairings.get_total_rounds(8), Ok(4));
+        assert_eq!(pairings.get_total_rounds(9), Ok(6));
+        assert_eq!(pairings.get_total_rounds(32), Ok(6));
+        assert_eq!(pairings.get_total_rounds(33), Ok(8));
+        assert_eq!(pairings.get_total_rounds(128), Ok(8));
+        assert_eq!(pairings.get_total_rounds(129), Ok(10));
+        assert_eq!(pairings.get_total_rounds(usize::MAX), Ok(10));
+    }
+
+    #[test]
+    fn single_swiss_top_cut() {
+        let pairings = Pairings::new_single_swiss();
+
+        assert_eq!(pairings.get_top_cut(2), Ok(None));
+        assert_eq!(pairings.get_top_cut(11), Ok(None));
+        assert_eq!(pairings.get_top_cut(12), Ok(Some(4)));
+        assert_eq!(pairings.get_top_cut(24), Ok(Some(4)));
+        assert_eq!(pairings.get_top_cut(25), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(128), Ok(Some(8)));
+        assert_eq!(pairings.get_top_cut(129), Ok(Some(16)));
+        assert_eq!(pairings.get_top_cut(usize::MAX), Ok(Some(16)));
+    }
+
+    #[test]
+    fn single_swiss_two_players() {
+        let players = vec![
+            Player::new("afirst", "alast", None),
+            Player::new("bfirst", "blast", None),
+        ];
+
+        let mut pairings = Pairings::new_single_swiss();
+        register_players(&mut pairings, &players);
+
+        let mut corp = vec![];
+        for _ in 0..pairings.get_total_rounds(players.len()).unwrap() {
+            let round = pairings.next_round().unwrap();
+            assert_eq!(round.len(), 1);
+            assert_eq!(
+                round[0].get_player_side(round[0].get_player()),
+                Some(Side::Corp)
+            );
+
+            corp.push(round[0].get_player().clone());
+
+            // only one game is played each round
+            assert_eq!(
+                pairings.round_ended(vec![(&round[0], Result::Win), (&round[0], Result::Win)]),
+                Err(PairingsError::InvalidResults(ResultsError::GameCount {
+                    player: round[0].get_player().get_id().clone(),
+                    expected: 1,
+                    actual: 2,
+                }))
+            );
+            pairings
+                .round_ended(vec![(&round[0], Result::Win)])
+                .unwrap();
+        }
+
+        // sides should alternate as the players rematch
+        assert_ne!(corp[0], corp[1]);
+        assert_ne!(corp[2], corp[3]);
+
+        for player in &players {
+            let stats = single_swiss_stats(&pairings, player);
+            assert_eq!(stats.corp_games(), 2);
+            assert_eq!(stats.runner_games(), 2);
+            assert_eq!(stats.swiss.score, 6);
+        }
+    }
+
+    #[test]
+    fn single_swiss_side_balance() {
+        let players = (0..9)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_single_swiss();
+        register_players(&mut pairings, &players);
+
+        let mut played = HashSet::new();
+        for _ in 0..pairings.get_total_rounds(players.len()).unwrap() {
+            let round = pairings.next_round().unwrap();
+            assert_eq!(round.len(), 5);
+
+            let mut results = vec![];
+            for pairing in &round {
+                match pairing.get_opponent() {
+                    Some(opponent) => {
+                        // never play the same opponent on the same side
+                        assert!(played.insert((
+                            pairing.get_player().get_id().clone(),
+                            opponent.get_id().clone()
+                        )));
+
+                        results.push((pairing, Result::Win));
+                    }
+                    None => results.push((pairing, Result::Bye)),
+                }
+            }
+            pairings.round_ended(results).unwrap();
+
+            for player in &players {
+                let stats = single_swiss_stats(&pairings, player);
+                assert!(stats.side_balance().abs() <= MAX_SIDE_BALANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn round_order() {
+        let players = vec![
+            Player::new("afirst", "alast", None),
+            Player::new("bfirst", "blast", None),
+        ];
+
+        let mut pairings = Pairings::new_swiss();
+
+        assert_eq!(
+            pairings.round_ended(vec![]),
+            Err(PairingsError::RoundNotStarted)
+        );
+
+        register_players(&mut pairings, &players);
+        let first_round = pairings.next_round().unwrap();
+        assert_eq!(pairings.next_round(), Err(PairingsError::RoundInProgress));
+
+        draw_round(&mut pairings, &first_round);
+        assert_eq!(
+            pairings.round_ended(vec![
+                (&first_round[0], Result::Draw),
+                (&first_round[0], Result::Draw),
+            ]),
+            Err(PairingsError::RoundAlreadyEnded)
+        );
+
+        assert!(pairings.next_round().is_ok());
+    }
+
+    #[test]
+    fn invalid_players() {
+        let player = Player::new("afirst", "alast", None);
+
+        let mut pairings = Pairings::new_swiss();
+
+        pairings.register_player(player.clone()).unwrap();
+        assert_eq!(
+            pairings.next_round(),
+            Err(PairingsError::NotEnoughPlayers(1))
+        );
+        assert_eq!(
+            pairings.register_player(player.clone()),
+            Err(PairingsError::DuplicatePlayer(player.get_id().clone()))
+        );
+
+        let unknown = Player::new("bfirst", "blast", None);
+        assert_eq!(
+            pairings.drop_player(&unknown),
+            Err(PairingsError::UnknownPlayer(unknown.get_id().clone()))
+        );
+
+        let mut players = vec![unknown, player];
+        assert_eq!(
+            pairings.algorithm.rank_players(&mut players),
+            Err(PairingsError::UnknownPlayer(players[0].get_id().clone()))
+        );
+    }
+
+    #[test]
+    fn seeded_pairings() {
+        let players = (0..9)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut x = Pairings::new_swiss().with_rng(&mut ChaCha8Rng::seed_from_u64(1234));
+        register_players(&mut x, &players);
+
+        let mut y = Pairings::new_swiss().with_seed(x.get_seed());
+        register_players(&mut y, &players);
+
+        for _ in 0..x.get_total_rounds(players.len()).unwrap() {
+            let xround = x.next_round().unwrap();
+            let yround = y.next_round().unwrap();
+            assert_eq!(xround, yround);
+
+            draw_round(&mut x, &xround);
+            draw_round(&mut y, &yround);
+        }
+
+        // the random tiebreakers should match as well
+        let mut xranked = players.clone();
+        x.algorithm.rank_players(&mut xranked).unwrap();
+
+        let mut yranked = players.clone();
+        y.algorithm.rank_players(&mut yranked).unwrap();
+
+        assert_eq!(xranked, yranked);
+    }
+
+    #[test]
+    fn random_tiebreak() {
+        let players = (0..4)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut stats = HashMap::new();
+        for (idx, player) in players.iter().enumerate() {
+            stats.insert(
+                player.get_id().clone(),
+                SwissStats {
+                    tiebreak: [7, 3, 9, 1][idx],
+                    ..Default::default()
+                },
+            );
+        }
+        let algorithm = PairingsAlgorithm::Swiss(stats);
+
+        // ranking is stable no matter how often it's done
+        for _ in 0..4 {
+            let mut ranked = players.clone();
+            ranked.shuffle(&mut thread_rng());
+            algorithm.rank_players(&mut ranked).unwrap();
+            assert_eq!(
+                ranked,
+                vec![
+                    players[3].clone(),
+                    players[1].clone(),
+                    players[0].clone(),
+                    players[2].clone()
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn byes() {
+        let players = (0..5)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+        assert!(pairings.players_with_byes().is_empty());
+
+        // 5 rounds so that every player should get exactly one bye
+        for round in 0..players.len() {
+            // should go to the lowest ranked player without a bye
+            // (the first round is random)
+            let expected = (round > 0).then(|| {
+                let mut ranked = players.clone();
+                pairings.algorithm.rank_players(&mut ranked).unwrap();
+                ranked
+                    .into_iter()
+                    .rev()
+                    .find(|player| !pairings.players_with_byes().contains(&player))
+                    .unwrap()
+            });
+
+            let pairings_round = pairings.next_round().unwrap();
+            let bye = pairings_round
+                .iter()
+                .find(|pairing| pairing.get_opponent().is_none())
+                .unwrap();
+            if let Some(expected) = expected {
+                assert_eq!(*bye.get_player(), expected);
+            }
+
+            draw_round(&mut pairings, &pairings_round);
+            assert_eq!(pairings.players_with_byes().len(), round + 1);
+            assert_eq!(swiss_stats(&pairings, bye.get_player()).byes, 1);
+        }
+
+        // then the second byes start
+        let pairings_round = pairings.next_round().unwrap();
+        let bye = pairings_round
+            .iter()
+            .find(|pairing| pairing.get_opponent().is_none())
+            .unwrap();
+        draw_round(&mut pairings, &pairings_round);
+        assert_eq!(swiss_stats(&pairings, bye.get_player()).byes, 2);
+        assert_eq!(pairings.players_with_byes().len(), players.len());
+    }
+
+    #[test]
+    fn drop_player() {
+        let players = (0..4)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        let first_round = pairings.next_round().unwrap();
+
+        // dropping during a round still needs the round reported
+        let dropped = first_round[0].get_player().clone();
+        let opponent = first_round[0].get_opponent().clone().unwrap();
+        pairings.drop_player(&dropped).unwrap();
+        assert_eq!(
+            pairings.drop_player(&dropped),
+            Err(PairingsError::PlayerDropped(dropped.get_id().clone()))
+        );
+        assert!(pairings.is_dropped(&dropped));
+        assert_eq!(pairings.get_active_players().len(), 3);
+        assert_eq!(pairings.get_players().len(), 4);
+
+        draw_round(&mut pairings, &first_round);
+
+        let second_round = pairings.next_round().unwrap();
+        assert_eq!(second_round.len(), 2);
+        assert!(!second_round
+            .iter()
+            .any(|pairing| pairing.has_player(&dropped)));
+        draw_round(&mut pairings, &second_round);
+
+        // dropped players still count towards their opponent's tiebreakers
+        let dropped_stats = swiss_stats(&pairings, &dropped);
+        assert_eq!(dropped_stats.score, 2);
+        assert_eq!(dropped_stats.rounds_played, 1);
+        assert!(swiss_stats(&pairings, &opponent)
+            .opponents
+            .contains(dropped.get_id()));
+        assert!(swiss_stats(&pairings, &opponent).sos > 0.0);
+
+        // and can re-enter having lost the missed round
+        pairings.late_join(dropped.clone()).unwrap();
+        assert!(!pairings.is_dropped(&dropped));
+        assert_eq!(
+            pairings.late_join(dropped.clone()),
+            Err(PairingsError::DuplicatePlayer(dropped.get_id().clone()))
+        );
+
+        let dropped_stats = swiss_stats(&pairings, &dropped);
+        assert_eq!(dropped_stats.score, 2);
+        assert_eq!(dropped_stats.rounds_played, 2);
+
+        let third_round = pairings.next_round().unwrap();
+        assert!(third_round
+            .iter()
+            .any(|pairing| pairing.has_player(&dropped)));
+    }
+
+    #[test]
+    fn late_join() {
+        let players = (0..4)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss().with_late_join_scoring(LateJoinScoring::Bye);
+        register_players(&mut pairings, &players);
+
+        let first_round = pairings.next_round().unwrap();
+        draw_round(&mut pairings, &first_round);
+
+        let late = Player::new("latefirst", "latelast", None);
+        assert_eq!(
+            pairings.register_player(late.clone()),
+            Err(PairingsError::TournamentStarted)
+        );
+        pairings.late_join(late.clone()).unwrap();
+
+        // late joiner gets the points for a bye, but not the bye itself
+        let late_stats = swiss_stats(&pairings, &late);
+        assert_eq!(late_stats.score, 6);
+        assert_eq!(late_stats.rounds_played, 1);
+        assert_eq!(late_stats.byes, 0);
+        assert_eq!(late_stats.record.get_byes(), 2);
+        assert!(pairings.players_with_byes().is_empty());
+
+        let second_round = pairings.next_round().unwrap();
+        assert_eq!(second_round.len(), 3);
+        assert!(second_round.iter().any(|pairing| pairing.has_player(&late)));
+
+        // joining during a round misses that round too
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        pairings.next_round().unwrap();
+        pairings.late_join(late.clone()).unwrap();
+
+        let late_stats = swiss_stats(&pairings, &late);
+        assert_eq!(late_stats.score, 0);
+        assert_eq!(late_stats.rounds_played, 1);
+        assert_eq!(late_stats.record.get_losses(), 2);
+
+        // missed rounds are scored as the configured losses
+        let mut pairings = Pairings::new_swiss().with_scoring_system(ScoringSystem::new(3, 1, 1));
+        register_players(&mut pairings, &players);
+
+        let first_round = pairings.next_round().unwrap();
+        draw_round(&mut pairings, &first_round);
+        pairings.late_join(late.clone()).unwrap();
+
+        let late_stats = swiss_stats(&pairings, &late);
+        assert_eq!(late_stats.score, 2);
+        assert_eq!(late_stats.record.to_string(), "0-2-0-0");
+        assert_eq!(late_stats.byes, 0);
+    }
+
+    #[test]
+    fn standings() {
+        let players = (0..17)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        let first_round = pairings.next_round().unwrap();
+        let results = first_round
+            .iter()
+            .flat_map(|pairing| match pairing.get_opponent() {
+                // first player wins as the Corp and draws as the Runner
+                Some(_) => [(pairing, Result::Win), (pairing, Result::Draw)],
+                None => [(pairing, Result::Bye), (pairing, Result::Bye)],
+            })
+            .collect::<Vec<_>>();
+        pairings.round_ended(results).unwrap();
+
+        // the leader drops
+        let leader = first_round[0].get_player().clone();
+        pairings.drop_player(&leader).unwrap();
+
+        let standings = pairings.standings().unwrap();
+        assert_eq!(standings.len(), players.len());
+        for (idx, standing) in standings.iter().enumerate() {
+            assert_eq!(standing.get_rank(), idx + 1);
+        }
+
+        // the bye is in the lead
+        assert_eq!(*standings[0].get_player(), leader);
+        assert_eq!(standings[0].get_score(), 6);
+        assert_eq!(standings[0].get_record().to_string(), "0-0-0-2");
+        assert_eq!(standings[0].get_corp_record().get_games(), 0);
+        assert!(!standings[0].is_top_cut());
+
+        for standing in &standings[1..9] {
+            assert_eq!(standing.get_score(), 4);
+            assert_eq!(standing.get_record().to_string(), "1-0-1-0");
+            assert_eq!(standing.get_corp_record().to_string(), "1-0-0-0");
+            assert_eq!(standing.get_runner_record().to_string(), "0-0-1-0");
+            assert_eq!(standing.get_sos(), 1.0);
+        }
+
+        for standing in &standings[9..] {
+            assert_eq!(standing.get_score(), 1);
+            assert_eq!(standing.get_record().to_string(), "0-1-1-0");
+            assert_eq!(standing.get_corp_record().to_string(), "0-0-1-0");
+            assert_eq!(standing.get_runner_record().to_string(), "0-1-0-0");
+            assert_eq!(standing.get_sos(), 4.0);
+        }
+
+        // top 4 cut, skipping the dropped player
+        let cut = standings
+            .iter()
+            .filter(|standing| standing.is_top_cut())
+            .map(|standing| standing.get_rank())
+            .collect::<Vec<_>>();
+        assert_eq!(cut, vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn start_top_cut() {
+        let players = (0..16)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        let round = pairings.next_round().unwrap();
+        assert_eq!(
+            pairings.start_top_cut().unwrap_err(),
+            PairingsError::RoundInProgress
+        );
+        draw_round(&mut pairings, &round);
+
+        let standings = pairings.standings().unwrap();
+        let elimination = pairings.start_top_cut().unwrap();
+        let seeds = standings
+            .iter()
+            .filter(|standing| standing.is_top_cut())
+            .map(|standing| standing.get_player().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(seeds.len(), 4);
+        assert_eq!(*elimination.get_seeds(), seeds);
+
+        elimination.next_round(|_, _| Side::Corp).unwrap();
+        assert_eq!(pairings.get_elimination().unwrap().get_rounds().len(), 1);
+
+        assert_eq!(
+            pairings.next_round().unwrap_err(),
+            PairingsError::TopCutStarted
+        );
+        assert_eq!(
+            pairings.start_top_cut().unwrap_err(),
+            PairingsError::TopCutStarted
+        );
+    }
+
+    #[test]
+    fn double_elimination_top_cut() {
+        let players = (0..25)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss().with_elimination_format(EliminationFormat::Double);
+        register_players(&mut pairings, &players);
+
+        let round = pairings.next_round().unwrap();
+        draw_round(&mut pairings, &round);
+
+        let elimination = pairings.start_top_cut().unwrap();
+        assert_eq!(elimination.get_format(), EliminationFormat::Double);
+        assert_eq!(elimination.get_seeds().len(), 8);
+        assert_eq!(elimination.get_total_rounds(), 6);
+    }
+
+    #[test]
+    fn no_top_cut() {
+        let players = (0..8)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        assert_eq!(
+            pairings.start_top_cut().unwrap_err(),
+            PairingsError::NoTopCut
+        );
+        assert!(pairings.get_elimination().is_none());
+    }
+
+    #[test]
+    fn serialization() {
+        let players = (0..9)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_single_swiss();
+        register_players(&mut pairings, &players);
+
+        for _ in 0..2 {
+            let round = pairings.next_round().unwrap();
+            let results = round
+                .iter()
+                .map(|pairing| match pairing.get_opponent() {
+                    Some(_) => (pairing, Result::Win),
+                    None => (pairing, Result::Bye),
+                })
+                .collect::<Vec<_>>();
+            pairings.round_ended(results).unwrap();
+        }
+        pairings.drop_player(&players[0]).unwrap();
+
+        let saved = serde_json::to_string(&pairings).unwrap();
+        let mut restored: Pairings = serde_json::from_str(&saved).unwrap();
+        assert_eq!(restored.get_format_version(), FORMAT_VERSION);
+        assert_eq!(restored.get_seed(), pairings.get_seed());
+        assert_eq!(restored.get_players(), pairings.get_players());
+        assert!(restored.is_dropped(&players[0]));
+
+        let standings = pairings.standings().unwrap();
+        let restored_standings = restored.standings().unwrap();
+        for (standing, restored_standing) in standings.iter().zip(&restored_standings) {
+            assert_eq!(standing.get_player(), restored_standing.get_player());
+            assert_eq!(standing.get_score(), restored_standing.get_score());
+            assert_eq!(standing.get_sos(), restored_standing.get_sos());
+            assert_eq!(standing.get_record(), restored_standing.get_record());
+        }
+
+        // the restored tournament picks up exactly where it left off
+        let round = pairings.next_round().unwrap();
+        let restored_round = restored.next_round().unwrap();
+        assert_eq!(round, restored_round);
+        for (pairing, restored_pairing) in round.iter().zip(&restored_round) {
+            assert_eq!(
+                pairing.get_player_side(pairing.get_player()),
+                restored_pairing.get_player_side(restored_pairing.get_player())
+            );
+        }
+    }
+
+    #[test]
+    fn newer_format_version() {
+        let pairings = Pairings::new_swiss();
+
+        let mut saved = serde_json::to_value(&pairings).unwrap();
+        saved["version"] = (FORMAT_VERSION + 1).into();
+        assert!(serde_json::from_value::<Pairings>(saved).is_err());
+    }
+
+    #[test]
+    fn scoring_system() {
+        let players = (0..3)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let scoring = ScoringSystem::new(2, 1, 0)
+            .with_bye_points(1)
+            .with_timed_win_points(1, 0);
+        let mut pairings = Pairings::new_swiss()
+            .with_seed(0)
+            .with_scoring_system(scoring);
+        register_players(&mut pairings, &players);
+
+        let round = pairings.next_round().unwrap();
+        let results = round
+            .iter()
+            .flat_map(|pairing| match pairing.get_opponent() {
+                Some(_) => [(pairing, Result::Win), (pairing, Result::TimedWin)],
+                None => [(pairing, Result::Bye), (pairing, Result::Bye)],
+            })
+            .collect::<Vec<_>>();
+        pairings.round_ended(results).unwrap();
+
+        let standings = pairings.standings().unwrap();
+        let scores = standings
+            .iter()
+            .map(|standing| standing.get_score())
+            .collect::<Vec<_>>();
+        assert_eq!(scores, vec![3, 2, 0]);
+        assert_eq!(standings[0].get_record().to_string(), "2-0-0-0");
+
+        // the winner's opponent scored nothing, the loser's opponent 3 points in 1 round
+        assert_eq!(standings[0].get_sos(), 0.0);
+        assert_eq!(standings[2].get_sos(), 3.0);
+    }
+
+    #[test]
+    fn decklists() {
+        let identity =
+            Identity::new("Hoshiko Shiro: Untold Protagonist").with_faction(Faction::Anarch);
+        let player =
+            Player::new("first", "last", None).with_identity(Side::Runner, identity.clone());
+
+        let mut pairings = Pairings::new_swiss();
+        pairings.register_player(player.clone()).unwrap();
+
+        let decklist = Decklist::new(None, vec![DeckCard::new("Sure Gamble", 3)]);
+        pairings
+            .set_decklist(&player, Side::Runner, decklist.clone())
+            .unwrap();
+        assert!(pairings
+            .set_decklist(
+                &Player::new("first", "last", None),
+                Side::Corp,
+                decklist.clone()
+            )
+            .is_err());
+
+        let restored: Pairings =
+            serde_json::from_str(&serde_json::to_string(&pairings).unwrap()).unwrap();
+        let restored_player = &restored.get_players()[0];
+        assert_eq!(restored_player.get_identity(Side::Runner), Some(&identity));
+        assert_eq!(restored_player.get_identity(Side::Corp), None);
+        assert_eq!(
+            restored.get_decklist(&player, Side::Runner),
+            Some(&decklist)
+        );
+        assert_eq!(restored.get_decklist(&player, Side::Corp), None);
+    }
+
+    #[test]
+    fn check_decklists() {
+        let database = CardDatabase::sample();
+        let player = Player::new("first", "last", None)
+            .with_identity(Side::Runner, Identity::new("Noise: Hacker Extraordinaire"));
+
+        let mut pairings = Pairings::new_swiss();
+        pairings.register_player(player.clone()).unwrap();
+        pairings
+            .set_decklist(
+                &player,
+                Side::Runner,
+                Decklist::new(None, vec![DeckCard::new("Sure Gamble", 45)]),
+            )
+            .unwrap();
+
+        let violations = pairings.check_decklists(&database, Format::Eternal);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].get_player(), player.get_id());
+        assert_eq!(violations[0].get_side(), Side::Runner);
+        assert_eq!(
+            violations[0].get_violation(),
+            &Violation::TooManyCopies {
+                card: "Sure Gamble".to_owned(),
+                limit: 3,
+                count: 45,
+            }
+        );
+
+        let violations = pairings.check_decklists(&database, Format::Standard);
+        assert_eq!(
+            violations[0].get_violation(),
+            &Violation::Rotated("Noise: Hacker Extraordinaire".to_owned())
+        );
+    }
+
+    #[test]
+    fn game_sides() {
+        let players = (0..2)
+            .map(|idx| Player::new(format!("first{}", idx), format!("last{}", idx), None))
+            .collect::<Vec<_>>();
+
+        let mut pairings = Pairings::new_swiss();
+        register_players(&mut pairings, &players);
+
+        let round = pairings.next_round().unwrap();
+        let pairing = &round[0];
+        pairings
+            .round_ended([(pairing, Result::Win), (pairing, Result::Draw)])
+            .unwrap();
+
+        let pairing = &pairings.get_rounds()[0].get_pairings()[0];
+        let (player, opponent) = (
+            pairing.get_player(),
+            pairing.get_opponent().clone().unwrap(),
+        );
+        let games = pairing.get_games();
+        assert_eq!(games[0].get_side(), Some(Side::Corp));
+        assert_eq!(games[1].get_side(), Some(Side::Runner));
+        assert_eq!(pairing.get_corp(&games[0]), Some(player));
+        assert_eq!(pairing.get_runner(&games[0]), Some(&opponent));
+        assert_eq!(pairing.get_corp(&games[1]), Some(&opponent));
+
+        let opponent_games = pairing.get_player_games(&opponent);
+        assert_eq!(opponent_games[0].get_side(), Some(Side::Runner));
+        assert_eq!(opponent_games[0].get_result(), Result::Loss);
+
+        // format version 4 kept only the results, with sides implied by their order
+        let mut saved = serde_json::to_value(&pairings).unwrap();
+        let saved_pairing = &mut saved["rounds"][0]["pairings"][0];
+        saved_pairing["results"] = serde_json::json!(["Win", "Draw"]);
+        saved_pairing.as_object_mut().unwrap().remove("games");
+        saved["version"] = 4.into();
+
+        let restored: Pairings = serde_json::from_value(saved).unwrap();
+        assert_eq!(restored.get_format_version(), 4);
+        assert_eq!(
+            restored.get_rounds()[0].get_pairings()[0].get_games(),
+            games
+        );
 
-        // TODO: validate the updated results
+        // saving again upgrades the file to the current version
+        let saved = serde_json::to_value(&restored).unwrap();
+        assert_eq!(saved["version"], FORMAT_VERSION);
     }
 }
@@ -0,0 +1,664 @@
+//! Meta reports of identity and faction representation and win rates
+//!
+//! Reports cover the Swiss rounds and the top cut separately, and can be rendered
+//! as Markdown for sharing or as CSV for spreadsheets.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::{Faction, Pairings, Player, Record, Round, Side};
+
+/// Representation and results of an identity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdentityStats {
+    identity: String,
+    faction: Option<Faction>,
+    side: Side,
+    players: usize,
+    record: Record,
+}
+
+impl IdentityStats {
+    /// Gets the identity's name
+    #[inline]
+    pub fn get_identity(&self) -> &String {
+        &self.identity
+    }
+
+    /// Gets the identity's faction, if known
+    #[inline]
+    pub fn get_faction(&self) -> Option<Faction> {
+        self.faction
+    }
+
+    /// Gets the side the identity plays
+    #[inline]
+    pub fn get_side(&self) -> Side {
+        self.side
+    }
+
+    /// Gets the number of players who registered the identity
+    #[inline]
+    pub fn get_players(&self) -> usize {
+        self.players
+    }
+
+    /// Gets the results of games played with the identity
+    #[inline]
+    pub fn get_record(&self) -> Record {
+        self.record
+    }
+}
+
+/// Representation and results of a faction
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FactionStats {
+    faction: Faction,
+    players: usize,
+    record: Record,
+}
+
+impl FactionStats {
+    /// Gets the faction
+    #[inline]
+    pub fn get_faction(&self) -> Faction {
+        self.faction
+    }
+
+    /// Gets the number of players who registered an identity from the faction
+    #[inline]
+    pub fn get_players(&self) -> usize {
+        self.players
+    }
+
+    /// Gets the results of games played with the faction's identities
+    #[inline]
+    pub fn get_record(&self) -> Record {
+        self.record
+    }
+}
+
+/// Results of games between a Corp and a Runner identity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Matchup {
+    corp: String,
+    runner: String,
+
+    // from the Corp's point of view
+    record: Record,
+}
+
+impl Matchup {
+    /// Gets the Corp identity's name
+    #[inline]
+    pub fn get_corp(&self) -> &String {
+        &self.corp
+    }
+
+    /// Gets the Runner identity's name
+    #[inline]
+    pub fn get_runner(&self) -> &String {
+        &self.runner
+    }
+
+    /// Gets the results of the matchup's games, from the Corp's point of view
+    #[inline]
+    pub fn get_record(&self) -> Record {
+        self.record
+    }
+}
+
+/// Meta statistics for one stage of a tournament
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetaBreakdown {
+    players: usize,
+
+    // every game with a known side, from the Corp's point of view
+    corp: Record,
+
+    identities: Vec<IdentityStats>,
+    factions: Vec<FactionStats>,
+    matchups: Vec<Matchup>,
+}
+
+impl MetaBreakdown {
+    /// Gathers statistics for the given players from the games played in the rounds
+    ///
+    /// Players without a registered identity count towards the side win rates,
+    /// but not towards identities, factions or matchups
+    fn new(players: &[&Player], rounds: &[Round]) -> Self {
+        let mut identities = BTreeMap::new();
+        let mut factions = BTreeMap::new();
+        for player in players {
+            for side in [Side::Corp, Side::Runner] {
+                if let Some(stats) = identity_stats(&mut identities, player, side) {
+                    stats.players += 1;
+                    if let Some(faction) = stats.faction {
+                        factions
+                            .entry(faction.get_code())
+                            .or_insert_with(|| FactionStats {
+                                faction,
+                                players: 0,
+                                record: Record::default(),
+                            })
+                            .players += 1;
+                    }
+                }
+            }
+        }
+
+        let mut corp = Record::default();
+        let mut matchups = BTreeMap::new();
+        let games = rounds
+            .iter()
+            .flat_map(Round::get_pairings)
+            .flat_map(|pairing| pairing.get_games().iter().map(move |game| (pairing, game)));
+        for (pairing, game) in games {
+            let (Some(corp_player), Some(runner_player)) =
+                (pairing.get_corp(game), pairing.get_runner(game))
+            else {
+                continue;
+            };
+            let result = match game.get_side() {
+                Some(Side::Corp) => game.get_result(),
+                _ => game.get_result().opposite(),
+            };
+            corp.add(result);
+
+            let mut names = [None, None];
+            for (idx, (player, side, result)) in [
+                (corp_player, Side::Corp, result),
+                (runner_player, Side::Runner, result.opposite()),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let Some(stats) = identity_stats(&mut identities, player, side) else {
+                    continue;
+                };
+                stats.record.add(result);
+                names[idx] = Some(stats.identity.clone());
+
+                let faction = stats.faction;
+                if let Some(stats) =
+                    faction.and_then(|faction| factions.get_mut(faction.get_code()))
+                {
+                    stats.record.add(result);
+                }
+            }
+
+            if let [Some(corp), Some(runner)] = names {
+                matchups
+                    .entry((corp.clone(), runner.clone()))
+                    .or_insert_with(|| Matchup {
+                        corp,
+                        runner,
+                        record: Record::default(),
+                    })
+                    .record
+                    .add(result);
+            }
+        }
+
+        // most played first, then by name
+        let mut identities = identities.into_values().collect::<Vec<_>>();
+        identities.sort_by(|a, b| {
+            (a.side == Side::Runner)
+                .cmp(&(b.side == Side::Runner))
+                .then(b.players.cmp(&a.players))
+        });
+        let mut factions = factions.into_values().collect::<Vec<_>>();
+        factions.sort_by(|a, b| {
+            (a.faction.get_side() == Side::Runner)
+                .cmp(&(b.faction.get_side() == Side::Runner))
+                .then(b.players.cmp(&a.players))
+        });
+
+        Self {
+            players: players.len(),
+            corp,
+            identities,
+            factions,
+            matchups: matchups.into_values().collect(),
+        }
+    }
+
+    /// Gets the number of players in this stage
+    #[inline]
+    pub fn get_players(&self) -> usize {
+        self.players
+    }
+
+    /// Gets the results of every game for the given side
+    #[inline]
+    pub fn get_side_record(&self, side: Side) -> Record {
+        match side {
+            Side::Corp => self.corp,
+            Side::Runner => self.corp.opposite(),
+        }
+    }
+
+    /// Gets every registered identity, Corp first and most played first
+    #[inline]
+    pub fn get_identities(&self) -> &Vec<IdentityStats> {
+        &self.identities
+    }
+
+    /// Gets every faction with a registered identity, Corp first and most played first
+    #[inline]
+    pub fn get_factions(&self) -> &Vec<FactionStats> {
+        &self.factions
+    }
+
+    /// Gets every Corp and Runner identity pair that played a game, by Corp then Runner name
+    #[inline]
+    pub fn get_matchups(&self) -> &Vec<Matchup> {
+        &self.matchups
+    }
+
+    /// Gets the share of this stage's players who registered a side's identity or faction
+    fn share(&self, players: usize) -> String {
+        if self.players == 0 {
+            return "-".to_owned();
+        }
+        format!("{:.1}%", players as f64 * 100.0 / self.players as f64)
+    }
+
+    fn write_markdown(&self, out: &mut String, title: &str) {
+        let corp = self.get_side_record(Side::Corp);
+        let _ = writeln!(out, "## {}\n", title);
+        let _ = writeln!(
+            out,
+            "{} players, {} games: Corp won {}, Runner won {}, {} drawn\n",
+            self.players,
+            corp.get_games(),
+            percent(corp.get_win_rate()),
+            percent(self.get_side_record(Side::Runner).get_win_rate()),
+            corp.get_draws()
+        );
+
+        let _ = writeln!(out, "### Identities\n");
+        let _ = writeln!(
+            out,
+            "| Identity | Side | Players | Share | Wins | Losses | Draws | Win rate |"
+        );
+        let _ = writeln!(out, "| --- | --- | --: | --: | --: | --: | --: | --: |");
+        for stats in &self.identities {
+            let _ = writeln!(
+                out,
+                "| {} | {:?} | {} | {} | {} |",
+                stats.identity.replace('|', "\\|"),
+                stats.side,
+                stats.players,
+                self.share(stats.players),
+                record_columns(stats.record, " | ")
+            );
+        }
+
+        let _ = writeln!(out, "\n### Factions\n");
+        let _ = writeln!(
+            out,
+            "| Faction | Side | Players | Share | Wins | Losses | Draws | Win rate |"
+        );
+        let _ = writeln!(out, "| --- | --- | --: | --: | --: | --: | --: | --: |");
+        for stats in &self.factions {
+            let _ = writeln!(
+                out,
+                "| {} | {:?} | {} | {} | {} |",
+                stats.faction,
+                stats.faction.get_side(),
+                stats.players,
+                self.share(stats.players),
+                record_columns(stats.record, " | ")
+            );
+        }
+
+        let _ = writeln!(out, "\n### Matchups\n");
+        let _ = writeln!(
+            out,
+            "| Corp | Runner | Corp wins | Runner wins | Draws | Corp win rate |"
+        );
+        let _ = writeln!(out, "| --- | --- | --: | --: | --: | --: |");
+        for matchup in &self.matchups {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                matchup.corp.replace('|', "\\|"),
+                matchup.runner.replace('|', "\\|"),
+                record_columns(matchup.record, " | ")
+            );
+        }
+    }
+}
+
+/// Gets the statistics for the identity a player registered for a side, adding it if needed
+fn identity_stats<'a>(
+    identities: &'a mut BTreeMap<(bool, String), IdentityStats>,
+    player: &Player,
+    side: Side,
+) -> Option<&'a mut IdentityStats> {
+    let identity = player.get_identity(side)?;
+    let stats = identities
+        .entry((side == Side::Runner, identity.get_name().clone()))
+        .or_insert_with(|| IdentityStats {
+            identity: identity.get_name().clone(),
+            faction: identity.get_faction(),
+            side,
+            players: 0,
+            record: Record::default(),
+        });
+    Some(stats)
+}
+
+/// Formats a win rate as a percentage, or `-` if no games were played
+fn percent(rate: Option<f64>) -> String {
+    rate.map_or_else(|| "-".to_owned(), |rate| format!("{:.1}%", rate * 100.0))
+}
+
+/// Formats a record's wins, losses, draws and win rate with the given separator
+fn record_columns(record: Record, separator: &str) -> String {
+    [
+        record.get_wins().to_string(),
+        record.get_losses().to_string(),
+        record.get_draws().to_string(),
+        percent(record.get_win_rate()),
+    ]
+    .join(separator)
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Tables in a meta report
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MetaTable {
+    Identities,
+    Factions,
+    Matchups,
+}
+
+/// Meta report for a tournament, with the top cut broken down separately
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetaReport {
+    swiss: MetaBreakdown,
+    top_cut: Option<MetaBreakdown>,
+}
+
+impl MetaReport {
+    /// Creates a report from every reported game
+    ///
+    /// Games are attributed to the identities players registered,
+    /// and games without a recorded side are left out
+    pub fn from_pairings(pairings: &Pairings) -> Self {
+        let players = pairings.get_players().iter().collect::<Vec<_>>();
+        let top_cut = pairings.get_elimination().map(|elimination| {
+            let seeds = elimination.get_seeds().iter().collect::<Vec<_>>();
+            MetaBreakdown::new(&seeds, elimination.get_rounds())
+        });
+
+        Self {
+            swiss: MetaBreakdown::new(&players, pairings.get_rounds()),
+            top_cut,
+        }
+    }
+
+    /// Gets the statistics for every player in the Swiss rounds
+    #[inline]
+    pub fn get_swiss(&self) -> &MetaBreakdown {
+        &self.swiss
+    }
+
+    /// Gets the statistics for the top cut players in the elimination rounds,
+    /// if the top cut has started
+    #[inline]
+    pub fn get_top_cut(&self) -> Option<&MetaBreakdown> {
+        self.top_cut.as_ref()
+    }
+
+    fn get_stages(&self) -> Vec<(&'static str, &MetaBreakdown)> {
+        let mut stages = vec![("Swiss", &self.swiss)];
+        stages.extend(self.top_cut.as_ref().map(|top_cut| ("Top cut", top_cut)));
+        stages
+    }
+
+    /// Exports the report as JSON
+    #[inline]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the report as Markdown, with a section for each stage
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        for (idx, (title, breakdown)) in self.get_stages().into_iter().enumerate() {
+            if idx > 0 {
+                out.push('\n');
+            }
+            breakdown.write_markdown(&mut out, title);
+        }
+        out
+    }
+
+    /// Renders one of the report's tables as CSV, with a column for the stage
+    pub fn to_csv(&self, table: MetaTable) -> String {
+        let mut out = match table {
+            MetaTable::Identities => {
+                "stage,identity,faction,side,players,wins,losses,draws,win_rate\n"
+            }
+            MetaTable::Factions => "stage,faction,side,players,wins,losses,draws,win_rate\n",
+            MetaTable::Matchups => "stage,corp,runner,corp_wins,runner_wins,draws,corp_win_rate\n",
+        }
+        .to_owned();
+
+        let columns = |record: Record| {
+            let rate = record
+                .get_win_rate()
+                .map_or_else(String::new, |rate| format!("{:.3}", rate));
+            format!(
+                "{},{},{},{}",
+                record.get_wins(),
+                record.get_losses(),
+                record.get_draws(),
+                rate
+            )
+        };
+        for (stage, breakdown) in self.get_stages() {
+            match table {
+                MetaTable::Identities => {
+                    for stats in &breakdown.identities {
+                        let _ = writeln!(
+                            out,
+                            "{},{},{},{:?},{},{}",
+                            stage,
+                            csv_field(&stats.identity),
+                            stats.faction.map_or("", |faction| faction.get_code()),
+                            stats.side,
+                            stats.players,
+                            columns(stats.record)
+                        );
+                    }
+                }
+                MetaTable::Factions => {
+                    for stats in &breakdown.factions {
+                        let _ = writeln!(
+                            out,
+                            "{},{},{:?},{},{}",
+                            stage,
+                            stats.faction.get_code(),
+                            stats.faction.get_side(),
+                            stats.players,
+                            columns(stats.record)
+                        );
+                    }
+                }
+                MetaTable::Matchups => {
+                    for matchup in &breakdown.matchups {
+                        let _ = writeln!(
+                            out,
+                            "{},{},{},{}",
+                            stage,
+                            csv_field(&matchup.corp),
+                            csv_field(&matchup.runner),
+                            columns(matchup.record)
+                        );
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Identity, Result};
+
+    use super::*;
+
+    const CORPS: [(&str, Faction); 2] = [
+        ("Jinteki: Restoring Humanity", Faction::Jinteki),
+        ("NBN: Reality Plus", Faction::Nbn),
+    ];
+
+    const RUNNERS: [(&str, Faction); 2] = [
+        ("Zahya Sadeghi: Versatile Smuggler", Faction::Criminal),
+        ("Nathaniel \"Gnat\" Hall: One-of-a-Kind", Faction::Shaper),
+    ];
+
+    /// Plays a Swiss round the Corp wins every game of, then a top cut the Runner wins
+    fn tournament() -> Pairings {
+        let mut pairings = Pairings::new_swiss().with_seed(0);
+        for idx in 0..16 {
+            // the last player has no identities
+            let mut player = Player::new("first", idx.to_string(), None);
+            if idx < 15 {
+                let (corp, faction) = CORPS[usize::from(idx >= 4)];
+                player =
+                    player.with_identity(Side::Corp, Identity::new(corp).with_faction(faction));
+                let (runner, faction) = RUNNERS[idx % 2];
+                player =
+                    player.with_identity(Side::Runner, Identity::new(runner).with_faction(faction));
+            }
+            pairings.register_player(player).unwrap();
+        }
+
+        let round = pairings.next_round().unwrap();
+        let results = round
+            .iter()
+            .flat_map(|pairing| [(pairing, Result::Win), (pairing, Result::Loss)])
+            .collect::<Vec<_>>();
+        pairings.round_ended(results).unwrap();
+
+        let elimination = pairings.start_top_cut().unwrap();
+        while !elimination.is_finished() {
+            // pairings have the Corp player first
+            let round = elimination.next_round(|_, _| Side::Corp).unwrap();
+            let results = round
+                .iter()
+                .map(|pairing| (pairing, Result::Loss))
+                .collect::<Vec<_>>();
+            elimination.round_ended(results).unwrap();
+        }
+        pairings
+    }
+
+    #[test]
+    fn swiss() {
+        let report = MetaReport::from_pairings(&tournament());
+        let swiss = report.get_swiss();
+        assert_eq!(swiss.get_players(), 16);
+        assert_eq!(swiss.get_side_record(Side::Corp).get_wins(), 16);
+        assert_eq!(
+            swiss.get_side_record(Side::Runner).get_win_rate(),
+            Some(0.0)
+        );
+
+        let identities = swiss.get_identities();
+        assert_eq!(identities.len(), 4);
+        assert_eq!(identities[0].get_identity(), "NBN: Reality Plus");
+        assert_eq!(identities[0].get_players(), 11);
+        assert_eq!(identities[1].get_players(), 4);
+        assert_eq!(identities[2].get_side(), Side::Runner);
+
+        let corp_games: u64 = identities[..2]
+            .iter()
+            .map(|stats| stats.get_record().get_wins())
+            .sum();
+        assert_eq!(corp_games, 15);
+        assert!(identities[..2]
+            .iter()
+            .all(|stats| stats.get_record().get_win_rate() == Some(1.0)));
+
+        let factions = swiss.get_factions();
+        assert_eq!(factions[0].get_faction(), Faction::Nbn);
+        assert_eq!(factions[0].get_record(), identities[0].get_record());
+
+        let matchups = swiss.get_matchups();
+        assert!(matchups
+            .iter()
+            .all(|matchup| matchup.get_record().get_losses() == 0));
+        let matchup_games: u64 = matchups
+            .iter()
+            .map(|matchup| matchup.get_record().get_games())
+            .sum();
+        assert_eq!(matchup_games, 14);
+    }
+
+    #[test]
+    fn top_cut() {
+        let report = MetaReport::from_pairings(&tournament());
+        let top_cut = report.get_top_cut().unwrap();
+        assert_eq!(top_cut.get_players(), 4);
+        assert_eq!(top_cut.get_side_record(Side::Runner).get_wins(), 3);
+        assert_eq!(top_cut.get_side_record(Side::Corp).get_losses(), 3);
+        assert_eq!(
+            top_cut
+                .get_identities()
+                .iter()
+                .map(IdentityStats::get_players)
+                .sum::<usize>(),
+            8
+        );
+
+        let report = MetaReport::from_pairings(&Pairings::new_swiss());
+        assert_eq!(report.get_top_cut(), None);
+        assert_eq!(
+            report
+                .get_swiss()
+                .get_side_record(Side::Corp)
+                .get_win_rate(),
+            None
+        );
+    }
+
+    #[test]
+    fn render() {
+        let report = MetaReport::from_pairings(&tournament());
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("## Swiss\n\n16 players, 16 games: Corp won 100.0%"));
+        assert!(markdown.contains("\n## Top cut\n"));
+        assert!(markdown.contains("| NBN: Reality Plus | Corp | 11 | 68.8% |"));
+
+        let csv = report.to_csv(MetaTable::Identities);
+        assert!(csv.starts_with("stage,identity,faction,side,players,"));
+        assert!(
+            csv.contains("\nSwiss,\"Nathaniel \"\"Gnat\"\" Hall: One-of-a-Kind\",shaper,Runner,7,")
+        );
+
+        let csv = report.to_csv(MetaTable::Factions);
+        assert!(csv.contains("\nSwiss,nbn,Corp,11,"));
+        assert_eq!(
+            report.to_csv(MetaTable::Matchups).lines().next(),
+            Some("stage,corp,runner,corp_wins,runner_wins,draws,corp_win_rate")
+        );
+    }
+}
@@ -0,0 +1,1086 @@
+//! NRTM (Netrunner Tournament Manager) result file import and export
+//!
+//! NRTM result files are the JSON format accepted by most Netrunner results sites.
+//! Cobra's extra fields for single-sided Swiss and intentional draws are optional,
+//! so files from either source can be read with the same types.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    EliminationFormat, Identity, Pairing, Pairings, PairingsError, Player, Result, ScoringSystem,
+    Side,
+};
+
+/// Gets the game result for the points earned in a game under the tournament's scoring system
+pub(crate) fn points_result(
+    points: u64,
+    scoring: &ScoringSystem,
+) -> std::result::Result<Result, PairingsError> {
+    [Result::Win, Result::Draw, Result::Loss]
+        .into_iter()
+        .find(|result| scoring.get_points(*result) == points)
+        .ok_or_else(|| PairingsError::InvalidImport(format!("unexpected game score {}", points)))
+}
+
+/// Gets the name of the identity the player registered for the given side
+pub(crate) fn identity_name(player: &Player, side: Side) -> Option<String> {
+    player
+        .get_identity(side)
+        .map(|identity| identity.get_name().clone())
+}
+
+/// Splits a display name into a first and last name
+pub(crate) fn split_name(name: &str) -> (&str, &str) {
+    let name = name.trim();
+    name.split_once(char::is_whitespace)
+        .map(|(first, last)| (first, last.trim()))
+        .unwrap_or((name, ""))
+}
+
+/// Gets the id to export for each player, by player id
+///
+/// Players imported from a result file keep their numeric ids,
+/// and everyone else is numbered in registration order after the highest of those
+pub(crate) fn export_ids(pairings: &Pairings) -> HashMap<&str, u64> {
+    let imported = |player: &Player| {
+        let id = player.get_id().parse::<u64>().ok()?;
+        (id.to_string() == *player.get_id()).then_some(id)
+    };
+
+    let mut next = pairings
+        .get_players()
+        .iter()
+        .filter_map(imported)
+        .max()
+        .unwrap_or(0);
+    pairings
+        .get_players()
+        .iter()
+        .map(|player| {
+            let id = imported(player).unwrap_or_else(|| {
+                next += 1;
+                next
+            });
+            (player.get_id().as_str(), id)
+        })
+        .collect()
+}
+
+/// Formats a tiebreaker to 3 decimal places
+#[inline]
+pub(crate) fn format_tiebreaker(value: f32) -> String {
+    format!("{:.3}", value)
+}
+
+/// Side played in an elimination or single-sided Swiss game
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NrtmRole {
+    Corp,
+    Runner,
+}
+
+impl From<Side> for NrtmRole {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Corp => Self::Corp,
+            Side::Runner => Self::Runner,
+        }
+    }
+}
+
+impl From<NrtmRole> for Side {
+    fn from(role: NrtmRole) -> Self {
+        match role {
+            NrtmRole::Corp => Self::Corp,
+            NrtmRole::Runner => Self::Runner,
+        }
+    }
+}
+
+/// A player in the Swiss standings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NrtmPlayer {
+    pub id: u64,
+    pub name: String,
+    pub rank: usize,
+    pub corp_identity: Option<String>,
+    pub runner_identity: Option<String>,
+    pub match_points: u64,
+    pub strength_of_schedule: String,
+    pub extended_strength_of_schedule: String,
+
+    /// Corp games minus Runner games played in single-sided Swiss
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side_balance: Option<i64>,
+}
+
+/// A player in the elimination rounds
+///
+/// The seed is the player's Swiss rank
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NrtmEliminationPlayer {
+    pub id: u64,
+    pub name: String,
+    pub rank: usize,
+    pub seed: usize,
+}
+
+/// One side of a game
+///
+/// Swiss games have the points earned playing each side,
+/// elimination games have the side played and the winner.
+/// Single-sided Swiss games may also have the side played.
+/// The id is None for the missing opponent of a bye.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NrtmGamePlayer {
+    pub id: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<NrtmRole>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub winner: Option<bool>,
+
+    #[serde(default)]
+    pub runner_score: Option<u64>,
+
+    #[serde(default)]
+    pub corp_score: Option<u64>,
+
+    #[serde(default)]
+    pub combined_score: Option<u64>,
+}
+
+/// A single table in a round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NrtmGame {
+    pub table: usize,
+    pub player1: NrtmGamePlayer,
+    pub player2: NrtmGamePlayer,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub intentional_draw: bool,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub two_for_one: bool,
+
+    #[serde(default)]
+    pub elimination_game: bool,
+}
+
+/// A link to related resources
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NrtmLink {
+    pub rel: String,
+    pub href: String,
+}
+
+/// An NRTM tournament result file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NrtmTournament {
+    pub name: String,
+    pub date: String,
+    pub cut_to_top: usize,
+    pub preliminary_rounds: usize,
+    pub players: Vec<NrtmPlayer>,
+
+    #[serde(default)]
+    pub elimination_players: Vec<NrtmEliminationPlayer>,
+
+    pub rounds: Vec<Vec<NrtmGame>>,
+
+    #[serde(default)]
+    pub uploaded_from: Option<String>,
+
+    #[serde(default)]
+    pub links: Vec<NrtmLink>,
+}
+
+impl NrtmTournament {
+    /// Parses an NRTM result file
+    #[inline]
+    pub fn from_json(json: impl AsRef<str>) -> serde_json::Result<Self> {
+        serde_json::from_str(json.as_ref())
+    }
+
+    /// Writes the tournament as an NRTM result file
+    #[inline]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Exports the Swiss rounds and top cut of a tournament
+    ///
+    /// Players imported from a result file keep their ids,
+    /// and other players are numbered in registration order.
+    /// Game scores are the points for each result in the tournament's scoring system.
+    /// Single-sided games have the side played, and only a score for that side.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than 2 players are registered
+    pub fn from_pairings(
+        pairings: &Pairings,
+        name: impl Into<String>,
+        date: impl Into<String>,
+    ) -> std::result::Result<Self, PairingsError> {
+        let ids = export_ids(pairings);
+        let id = |player: &Player| ids[player.get_id().as_str()];
+        let scoring = pairings.get_scoring_system();
+
+        let players = pairings
+            .standings()?
+            .into_iter()
+            .map(|standing| NrtmPlayer {
+                id: id(standing.get_player()),
+                name: standing.get_player().get_nickname(),
+                rank: standing.get_rank(),
+                corp_identity: identity_name(standing.get_player(), Side::Corp),
+                runner_identity: identity_name(standing.get_player(), Side::Runner),
+                match_points: standing.get_score(),
+                strength_of_schedule: format_tiebreaker(standing.get_sos()),
+                extended_strength_of_schedule: format_tiebreaker(standing.get_esos()),
+                side_balance: pairings.is_single_sided().then(|| {
+                    standing.get_corp_record().get_games() as i64
+                        - standing.get_runner_record().get_games() as i64
+                }),
+            })
+            .collect();
+
+        let mut rounds = pairings
+            .get_rounds()
+            .iter()
+            .map(|round| {
+                round
+                    .get_pairings()
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, pairing)| NrtmGame {
+                        table: idx + 1,
+                        player1: game_player(pairing, pairing.get_player(), id, scoring),
+                        player2: match pairing.get_opponent() {
+                            Some(opponent) => game_player(pairing, opponent, id, scoring),
+                            None => NrtmGamePlayer::default(),
+                        },
+                        intentional_draw: !pairing.get_games().is_empty()
+                            && pairing
+                                .get_results()
+                                .iter()
+                                .all(|result| *result == Result::IntentionalDraw),
+                        two_for_one: false,
+                        elimination_game: false,
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+
+        let mut cut_to_top = pairings
+            .get_top_cut(pairings.get_players().len())?
+            .unwrap_or(0);
+        let mut elimination_players = vec![];
+        if let Some(elimination) = pairings.get_elimination() {
+            cut_to_top = elimination.get_seeds().len();
+
+            rounds.extend(elimination.get_rounds().iter().map(|round| {
+                round
+                    .get_pairings()
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, pairing)| elimination_game(idx + 1, pairing, id))
+                    .collect()
+            }));
+
+            // final ranks are only known once the cut is over
+            if elimination.is_finished() {
+                elimination_players = elimination
+                    .get_placements()
+                    .into_iter()
+                    .map(|placement| NrtmEliminationPlayer {
+                        id: id(placement.get_player()),
+                        name: placement.get_player().get_nickname(),
+                        rank: placement.get_place(),
+                        seed: elimination.get_seed(placement.get_player()).unwrap(),
+                    })
+                    .collect();
+            }
+        }
+
+        Ok(Self {
+            name: name.into(),
+            date: date.into(),
+            cut_to_top,
+            preliminary_rounds: pairings.get_rounds().len(),
+            players,
+            elimination_players,
+            rounds,
+            uploaded_from: Some(env!("CARGO_PKG_NAME").to_owned()),
+            links: vec![],
+        })
+    }
+
+    /// Imports the Swiss rounds and top cut of the tournament
+    ///
+    /// The tournament is single-sided if any Swiss game has the side played,
+    /// or only has a score for one side. Player ids are the NRTM player ids.
+    /// Game scores are read with the standard scoring system.
+    /// Elimination rounds without a winner for every game are left in progress.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the players or rounds are inconsistent,
+    /// if an unreported round is followed by other rounds,
+    /// or if the elimination rounds don't follow the bracket
+    pub fn to_pairings(&self) -> std::result::Result<Pairings, PairingsError> {
+        let swiss_rounds = self.get_rounds(false);
+
+        let single_sided = swiss_rounds.iter().flatten().any(|game| {
+            game.player1.role.is_some()
+                || game.player2.role.is_some()
+                || game.player2.id.is_some()
+                    && game.player1.corp_score.is_some() != game.player1.runner_score.is_some()
+        });
+
+        let mut pairings = if single_sided {
+            Pairings::new_single_swiss()
+        } else {
+            Pairings::new_swiss()
+        };
+
+        let mut players = self.players.iter().collect::<Vec<_>>();
+        players.sort_by_key(|player| player.id);
+        for player in players {
+            // the NRTM name is kept as the nickname, so it's exported unchanged
+            let (first_name, last_name) = split_name(&player.name);
+            let mut registered = Player::new_with_id(
+                player.id.to_string(),
+                first_name,
+                last_name,
+                Some(player.name.clone()),
+            );
+            if let Some(identity) = &player.corp_identity {
+                registered = registered.with_identity(Side::Corp, Identity::new(identity));
+            }
+            if let Some(identity) = &player.runner_identity {
+                registered = registered.with_identity(Side::Runner, Identity::new(identity));
+            }
+            pairings.register_player(registered)?;
+        }
+
+        for round in swiss_rounds {
+            let mut round_pairings = Vec::with_capacity(round.len());
+            let mut results = Some(vec![]);
+            for (idx, game) in round.into_iter().enumerate() {
+                let (pairing, game_results) = import_game(&pairings, game, single_sided)?;
+                round_pairings.push(pairing);
+
+                match game_results {
+                    Some(game_results) => {
+                        if let Some(results) = &mut results {
+                            results.extend(game_results.into_iter().map(|result| (idx, result)));
+                        }
+                    }
+                    None => results = None,
+                }
+            }
+
+            pairings.import_round(round_pairings, results)?;
+        }
+
+        self.import_elimination(&mut pairings)?;
+
+        Ok(pairings)
+    }
+
+    /// Imports the top cut into the imported Swiss rounds
+    ///
+    /// Seeds are taken from the elimination players, or else from the Swiss ranks.
+    /// The cut is double-elimination if a player plays on after losing a game.
+    fn import_elimination(
+        &self,
+        pairings: &mut Pairings,
+    ) -> std::result::Result<(), PairingsError> {
+        let rounds = self.get_rounds(true);
+        if rounds.is_empty() {
+            return Ok(());
+        }
+
+        let mut seeds = if self.elimination_players.is_empty() {
+            self.players
+                .iter()
+                .map(|player| (player.rank, player.id))
+                .collect::<Vec<_>>()
+        } else {
+            self.elimination_players
+                .iter()
+                .map(|player| (player.seed, player.id))
+                .collect()
+        };
+        seeds.sort();
+        seeds.truncate(self.cut_to_top);
+        let seeds = seeds
+            .into_iter()
+            .map(|(_, id)| {
+                pairings
+                    .get_player(id.to_string())
+                    .cloned()
+                    .ok_or_else(|| PairingsError::UnknownPlayer(id.to_string()))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut eliminated = vec![];
+        let mut double = false;
+        for game in rounds.iter().flatten() {
+            for player in [&game.player1, &game.player2] {
+                double |= eliminated.contains(&player.id);
+            }
+            for player in [&game.player1, &game.player2] {
+                if player.winner == Some(false) {
+                    eliminated.push(player.id);
+                }
+            }
+        }
+        let format = if double {
+            EliminationFormat::Double
+        } else {
+            EliminationFormat::Single
+        };
+
+        let elimination = pairings.import_top_cut(seeds, format)?;
+        for games in rounds {
+            let mut round_pairings = Vec::with_capacity(games.len());
+            let mut results = Some(vec![]);
+            for game in games {
+                let (pairing, result) = import_elimination_game(elimination.get_seeds(), game)?;
+                round_pairings.push(pairing);
+
+                match (result, &mut results) {
+                    (Some(result), Some(results)) => results.push(result),
+                    _ => results = None,
+                }
+            }
+
+            elimination.import_round(round_pairings, results)?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the non-empty Swiss or elimination rounds
+    fn get_rounds(&self, elimination: bool) -> Vec<Vec<&NrtmGame>> {
+        self.rounds
+            .iter()
+            .map(|round| {
+                round
+                    .iter()
+                    .filter(|game| game.elimination_game == elimination)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|round| !round.is_empty())
+            .collect()
+    }
+}
+
+/// Exports a single-sided elimination game
+fn elimination_game(table: usize, pairing: &Pairing, id: impl Fn(&Player) -> u64) -> NrtmGame {
+    let winner = pairing.get_winner();
+    let game_player = |player: &Player| NrtmGamePlayer {
+        id: Some(id(player)),
+        role: pairing.get_player_side(player).map(NrtmRole::from),
+        winner: winner.map(|winner| winner == player),
+        ..Default::default()
+    };
+
+    NrtmGame {
+        table,
+        player1: game_player(pairing.get_player()),
+        player2: pairing
+            .get_opponent()
+            .as_ref()
+            .map(game_player)
+            .unwrap_or_default(),
+        intentional_draw: false,
+        two_for_one: false,
+        elimination_game: true,
+    }
+}
+
+/// Exports one side of a pairing, with the points earned under the tournament's scoring system
+fn game_player(
+    pairing: &Pairing,
+    player: &Player,
+    id: impl Fn(&Player) -> u64,
+    scoring: &ScoringSystem,
+) -> NrtmGamePlayer {
+    let games = pairing.get_player_games(player);
+
+    let mut game_player = NrtmGamePlayer {
+        id: Some(id(player)),
+        role: pairing.get_player_side(player).map(NrtmRole::from),
+        ..Default::default()
+    };
+
+    if games.is_empty() {
+        return game_player;
+    }
+
+    let combined_score = games
+        .iter()
+        .map(|game| scoring.get_points(game.get_result()))
+        .sum();
+    game_player.combined_score = Some(combined_score);
+
+    if pairing.get_opponent().is_none() {
+        return game_player;
+    }
+
+    for game in &games {
+        let points = Some(scoring.get_points(game.get_result()));
+        match game.get_side() {
+            Some(Side::Corp) => game_player.corp_score = points,
+            Some(Side::Runner) => game_player.runner_score = points,
+            None => (),
+        }
+    }
+
+    game_player
+}
+
+/// Looks up the player for one side of a game
+///
+/// Returns None if the game has no player on that side
+fn import_player(
+    pairings: &Pairings,
+    game_player: &NrtmGamePlayer,
+) -> Option<std::result::Result<Player, PairingsError>> {
+    let id = game_player.id?.to_string();
+    Some(
+        pairings
+            .get_player(&id)
+            .cloned()
+            .ok_or(PairingsError::UnknownPlayer(id)),
+    )
+}
+
+/// Imports a single Swiss game as a pairing along with its results
+///
+/// Results are None if the game has not been reported
+fn import_game(
+    pairings: &Pairings,
+    game: &NrtmGame,
+    single_sided: bool,
+) -> std::result::Result<(Pairing, Option<Vec<Result>>), PairingsError> {
+    let scoring = pairings.get_scoring_system();
+    let (player1, player2) = match (
+        import_player(pairings, &game.player1),
+        import_player(pairings, &game.player2),
+    ) {
+        (Some(player1), player2) => (player1?, player2.transpose()?),
+        (None, Some(player2)) => (player2?, None),
+        (None, None) => {
+            return Err(PairingsError::InvalidImport(format!(
+                "table {} has no players",
+                game.table
+            )))
+        }
+    };
+
+    let Some(player2) = player2 else {
+        let games = if single_sided { 1 } else { 2 };
+        let reported =
+            game.player1.combined_score.is_some() || game.player2.combined_score.is_some();
+        return Ok((
+            Pairing::new_bye(player1),
+            reported.then(|| vec![Result::Bye; games]),
+        ));
+    };
+
+    if game.intentional_draw {
+        let games = if single_sided { 1 } else { 2 };
+        let pairing = if single_sided {
+            import_single_sided(game, player1, player2)?.0
+        } else {
+            Pairing::new(player1, player2)
+        };
+        return Ok((pairing, Some(vec![Result::IntentionalDraw; games])));
+    }
+
+    if single_sided {
+        let (pairing, score) = import_single_sided(game, player1, player2)?;
+        let results = score
+            .map(|score| points_result(score, scoring))
+            .transpose()?
+            .map(|result| vec![result]);
+        return Ok((pairing, results));
+    }
+
+    let results = match (game.player1.corp_score, game.player1.runner_score) {
+        (Some(corp), Some(runner)) => Some(vec![
+            points_result(corp, scoring)?,
+            points_result(runner, scoring)?,
+        ]),
+        _ => None,
+    };
+    Ok((Pairing::new(player1, player2), results))
+}
+
+/// Imports a single-sided Swiss game with the Corp player first
+///
+/// The side played is taken from the roles if there are any, or else from
+/// whichever player has a Corp score.
+/// Also gives the Corp player's score, if it has been reported
+fn import_single_sided(
+    game: &NrtmGame,
+    player1: Player,
+    player2: Player,
+) -> std::result::Result<(Pairing, Option<u64>), PairingsError> {
+    let side = match (game.player1.role, game.player2.role) {
+        (Some(role), _) => Side::from(role),
+        (None, Some(role)) => Side::from(role).opposite(),
+        (None, None) => match (game.player1.corp_score, game.player2.corp_score) {
+            (Some(_), _) => Side::Corp,
+            (None, Some(_)) => Side::Runner,
+            (None, None) => {
+                return Err(PairingsError::InvalidImport(format!(
+                    "table {} has no sides",
+                    game.table
+                )))
+            }
+        },
+    };
+
+    Ok(match side {
+        Side::Corp => (
+            Pairing::new_single_sided(player1, player2),
+            game.player1.corp_score,
+        ),
+        Side::Runner => (
+            Pairing::new_single_sided(player2, player1),
+            game.player2.corp_score,
+        ),
+    })
+}
+
+/// Imports a single elimination game between two seeds, with the Corp player first
+///
+/// Also gives the Corp player's result, if there is a winner
+fn import_elimination_game(
+    seeds: &[Player],
+    game: &NrtmGame,
+) -> std::result::Result<(Pairing, Option<Result>), PairingsError> {
+    let seed = |game_player: &NrtmGamePlayer| {
+        let id = game_player.id?.to_string();
+        Some(
+            seeds
+                .iter()
+                .find(|player| *player.get_id() == id)
+                .cloned()
+                .ok_or(PairingsError::UnknownPlayer(id)),
+        )
+    };
+    let (Some(player1), Some(player2)) = (seed(&game.player1), seed(&game.player2)) else {
+        return Err(PairingsError::InvalidImport(format!(
+            "elimination table {} is missing a player",
+            game.table
+        )));
+    };
+    let (player1, player2) = (player1?, player2?);
+
+    let side = match (game.player1.role, game.player2.role) {
+        (Some(role), _) => Side::from(role),
+        (None, Some(role)) => Side::from(role).opposite(),
+        (None, None) => {
+            return Err(PairingsError::InvalidImport(format!(
+                "elimination table {} has no sides",
+                game.table
+            )))
+        }
+    };
+
+    let winner = match (game.player1.winner, game.player2.winner) {
+        (Some(true), _) | (_, Some(false)) => Some(Result::Win),
+        (Some(false), _) | (_, Some(true)) => Some(Result::Loss),
+        (None, None) => None,
+    };
+
+    Ok(match side {
+        Side::Corp => (Pairing::new_single_sided(player1, player2), winner),
+        Side::Runner => (
+            Pairing::new_single_sided(player2, player1),
+            winner.map(|result| result.opposite()),
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Elimination;
+
+    const SAMPLE: &str = include_str!("../testdata/nrtm/sample.json");
+    const GAPS: &str = include_str!("../testdata/nrtm/gaps.json");
+
+    /// Gets the (Corp, Runner, winner) ids of each elimination game,
+    /// whichever order the players are listed in
+    fn elimination_games(tournament: &NrtmTournament) -> Vec<Vec<(u64, u64, u64)>> {
+        tournament
+            .get_rounds(true)
+            .into_iter()
+            .map(|games| {
+                games
+                    .into_iter()
+                    .map(|game| {
+                        let (corp, runner) = match game.player1.role {
+                            Some(NrtmRole::Corp) => (&game.player1, &game.player2),
+                            _ => (&game.player2, &game.player1),
+                        };
+                        let winner = if corp.winner == Some(true) {
+                            corp
+                        } else {
+                            runner
+                        };
+                        (corp.id.unwrap(), runner.id.unwrap(), winner.id.unwrap())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn import_swiss() {
+        let tournament = NrtmTournament::from_json(SAMPLE).unwrap();
+        let pairings = tournament.to_pairings().unwrap();
+        assert!(!pairings.is_single_sided());
+        assert_eq!(pairings.get_rounds().len(), tournament.preliminary_rounds);
+
+        let standings = pairings.standings().unwrap();
+        assert_eq!(standings.len(), tournament.players.len());
+
+        for (player, standing) in tournament.players.iter().zip(standings) {
+            assert_eq!(*standing.get_player().get_id(), player.id.to_string());
+            assert_eq!(standing.get_player().get_nickname(), player.name);
+            assert_eq!(standing.get_rank(), player.rank);
+            assert_eq!(standing.get_score(), player.match_points);
+            assert_eq!(
+                format_tiebreaker(standing.get_sos()),
+                player.strength_of_schedule
+            );
+            assert_eq!(
+                format_tiebreaker(standing.get_esos()),
+                player.extended_strength_of_schedule
+            );
+        }
+    }
+
+    #[test]
+    fn import_elimination() {
+        let tournament = NrtmTournament::from_json(SAMPLE).unwrap();
+        let pairings = tournament.to_pairings().unwrap();
+        let elimination = pairings.get_elimination().unwrap();
+        assert_eq!(elimination.get_format(), EliminationFormat::Single);
+        assert_eq!(
+            elimination
+                .get_seeds()
+                .iter()
+                .map(|player| player.get_id().as_str())
+                .collect::<Vec<_>>(),
+            ["1", "4", "3", "2"]
+        );
+
+        let rounds = elimination.get_rounds();
+        assert_eq!(rounds.len(), 2);
+        for round in rounds {
+            assert!(round.has_ended());
+            for pairing in round.get_pairings() {
+                assert_eq!(
+                    pairing.get_player_side(pairing.get_player()),
+                    Some(Side::Corp)
+                );
+            }
+        }
+
+        // the final was won by the Corp player, listed second
+        let final_pairing = &rounds[1].get_pairings()[0];
+        assert_eq!(final_pairing.get_player().get_id(), "3");
+        assert_eq!(final_pairing.get_results(), vec![Result::Win]);
+
+        // the second semi-final was won by the Runner player
+        let semi_final = &rounds[0].get_pairings()[1];
+        assert_eq!(semi_final.get_player().get_id(), "4");
+        assert_eq!(semi_final.get_results(), vec![Result::Loss]);
+
+        // the top cut is kept when exported again
+        let exported =
+            NrtmTournament::from_pairings(&pairings, &tournament.name, &tournament.date).unwrap();
+        let seeds = |tournament: &NrtmTournament| {
+            tournament
+                .elimination_players
+                .iter()
+                .map(|player| (player.id, player.seed))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(seeds(&exported), seeds(&tournament));
+        assert_eq!(elimination_games(&exported), elimination_games(&tournament));
+    }
+
+    #[test]
+    fn import_elimination_in_progress() {
+        // without final ranks the seeds are the Swiss ranks
+        let mut tournament = NrtmTournament::from_json(SAMPLE).unwrap();
+        tournament.elimination_players.clear();
+        tournament.rounds.pop();
+        let mut pairings = tournament.to_pairings().unwrap();
+
+        let elimination = pairings.get_elimination_mut().unwrap();
+        assert_eq!(elimination.get_seeds().len(), 4);
+        assert!(!elimination.is_finished());
+        let round = elimination.next_round(|_, _| Side::Corp).unwrap();
+        assert_eq!(round.len(), 1);
+        assert!(round[0].has_player(pairings.get_player("1").unwrap()));
+        assert!(round[0].has_player(pairings.get_player("3").unwrap()));
+
+        // elimination rounds have to follow the bracket
+        let mut tournament = NrtmTournament::from_json(SAMPLE).unwrap();
+        let round = &mut tournament.rounds[tournament.preliminary_rounds];
+        round[0].player2.id = Some(3);
+        round[1].player2.id = Some(2);
+        assert_eq!(
+            tournament.to_pairings().unwrap_err(),
+            PairingsError::InvalidImport(
+                "elimination round 1 is missing the match between 1 and 2".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn export() {
+        let tournament = NrtmTournament::from_json(SAMPLE).unwrap();
+        let pairings = tournament.to_pairings().unwrap();
+
+        let exported =
+            NrtmTournament::from_pairings(&pairings, &tournament.name, &tournament.date).unwrap();
+        let exported = NrtmTournament::from_json(exported.to_json().unwrap()).unwrap();
+
+        assert_eq!(exported.preliminary_rounds, tournament.preliminary_rounds);
+        assert_eq!(exported.players, tournament.players);
+        assert_eq!(
+            exported.rounds[..exported.preliminary_rounds],
+            tournament.rounds[..tournament.preliminary_rounds]
+        );
+        assert_eq!(elimination_games(&exported), elimination_games(&tournament));
+    }
+
+    #[test]
+    fn export_single_word_name() {
+        let mut tournament = NrtmTournament::from_json(SAMPLE).unwrap();
+        tournament.players[0].name = "Zed".to_owned();
+        let pairings = tournament.to_pairings().unwrap();
+
+        let player = pairings.get_player("1").unwrap();
+        assert_eq!(player.get_first_name(), "Zed");
+        assert_eq!(player.get_last_name(), "");
+
+        let exported =
+            NrtmTournament::from_pairings(&pairings, &tournament.name, &tournament.date).unwrap();
+        assert_eq!(exported.players, tournament.players);
+    }
+
+    #[test]
+    fn export_keeps_ids() {
+        // players removed during registration leave gaps in the ids
+        let tournament = NrtmTournament::from_json(GAPS).unwrap();
+        let mut pairings = tournament.to_pairings().unwrap();
+
+        let exported =
+            NrtmTournament::from_pairings(&pairings, &tournament.name, &tournament.date).unwrap();
+        assert_eq!(exported.players, tournament.players);
+        assert_eq!(exported.rounds, tournament.rounds);
+
+        // players who join later are numbered after the imported players
+        let late = Player::new("Late", "Player", None);
+        pairings.late_join(late.clone()).unwrap();
+        let ids = export_ids(&pairings);
+        assert_eq!(ids["6"], 6);
+        assert_eq!(ids[late.get_id().as_str()], 11);
+    }
+
+    #[test]
+    fn export_elimination() {
+        let mut pairings = Pairings::new_swiss();
+        for idx in 0..16 {
+            pairings
+                .register_player(Player::new("first", idx.to_string(), None))
+                .unwrap();
+        }
+
+        let round = pairings.next_round().unwrap();
+        let results = round
+            .iter()
+            .flat_map(|pairing| [(pairing, Result::Win), (pairing, Result::Loss)])
+            .collect::<Vec<_>>();
+        pairings.round_ended(results).unwrap();
+
+        // the Corp player wins every elimination game
+        let elimination = pairings.start_top_cut().unwrap();
+        while !elimination.is_finished() {
+            let round = elimination.next_round(|_, _| Side::Runner).unwrap();
+            let results = round
+                .iter()
+                .map(|pairing| (pairing, Result::Win))
+                .collect::<Vec<_>>();
+            elimination.round_ended(results).unwrap();
+        }
+        let champion = elimination.get_champion().unwrap().clone();
+
+        let exported = NrtmTournament::from_pairings(&pairings, "Cut", "2023-01-01").unwrap();
+        assert_eq!(exported.cut_to_top, 4);
+        assert_eq!(exported.preliminary_rounds, 1);
+        assert_eq!(exported.rounds.len(), 3);
+        assert!(exported.rounds[1..]
+            .iter()
+            .flatten()
+            .all(|game| game.elimination_game));
+
+        assert_eq!(exported.elimination_players.len(), 4);
+        let winner = &exported.elimination_players[0];
+        assert_eq!(winner.rank, 1);
+        assert_eq!(winner.name, champion.get_nickname());
+
+        let imported = exported.to_pairings().unwrap();
+        let elimination = imported.get_elimination().unwrap();
+        assert_eq!(elimination.get_rounds().len(), 2);
+        assert_eq!(
+            elimination.get_champion().unwrap().get_id(),
+            &winner.id.to_string()
+        );
+    }
+
+    #[test]
+    fn import_double_elimination() {
+        let mut pairings = Pairings::new_swiss().with_elimination_format(EliminationFormat::Double);
+        for idx in 0..16 {
+            pairings
+                .register_player(Player::new("first", idx.to_string(), None))
+                .unwrap();
+        }
+
+        let round = pairings.next_round().unwrap();
+        let results = round
+            .iter()
+            .flat_map(|pairing| [(pairing, Result::Win), (pairing, Result::Loss)])
+            .collect::<Vec<_>>();
+        pairings.round_ended(results).unwrap();
+
+        // the Corp player wins every elimination game
+        let elimination = pairings.start_top_cut().unwrap();
+        while !elimination.is_finished() {
+            let round = elimination.next_round(|_, _| Side::Runner).unwrap();
+            let results = round
+                .iter()
+                .map(|pairing| (pairing, Result::Win))
+                .collect::<Vec<_>>();
+            elimination.round_ended(results).unwrap();
+        }
+
+        let exported = NrtmTournament::from_pairings(&pairings, "Cut", "2023-01-01").unwrap();
+        let imported = exported.to_pairings().unwrap();
+        let (elimination, imported) = (
+            pairings.get_elimination().unwrap(),
+            imported.get_elimination().unwrap(),
+        );
+        assert_eq!(imported.get_format(), EliminationFormat::Double);
+        assert_eq!(imported.get_rounds().len(), elimination.get_rounds().len());
+
+        // imported players have new ids, so they are compared by name
+        let places = |elimination: &Elimination| {
+            elimination
+                .get_placements()
+                .into_iter()
+                .map(|placement| (placement.get_place(), placement.get_player().get_nickname()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(places(imported), places(elimination));
+    }
+
+    #[test]
+    fn export_scoring() {
+        let mut pairings = Pairings::new_swiss().with_scoring_system(ScoringSystem::new(2, 1, 0));
+        for name in ["A", "B"] {
+            pairings
+                .register_player(Player::new(name, name, None))
+                .unwrap();
+        }
+        let round = pairings.next_round().unwrap();
+        pairings
+            .round_ended([(&round[0], Result::Win), (&round[0], Result::Draw)])
+            .unwrap();
+
+        let exported = NrtmTournament::from_pairings(&pairings, "Scoring", "2023-01-01").unwrap();
+        let game = &exported.rounds[0][0];
+        assert_eq!(game.player1.corp_score, Some(2));
+        assert_eq!(game.player1.combined_score, Some(3));
+        assert_eq!(game.player2.combined_score, Some(1));
+    }
+
+    #[test]
+    fn single_sided() {
+        let mut pairings = Pairings::new_single_swiss();
+        for (first_name, last_name) in [("A", "A"), ("B", "B"), ("C", "C"), ("D", "D")] {
+            pairings
+                .register_player(Player::new(first_name, last_name, None))
+                .unwrap();
+        }
+
+        // every Corp player wins
+        pairings.next_round().unwrap();
+        let round = pairings.get_rounds().last().unwrap().get_pairings().clone();
+        pairings
+            .round_ended(
+                round
+                    .iter()
+                    .map(|pairing| (pairing, Result::Win))
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+
+        let exported = NrtmTournament::from_pairings(&pairings, "Single", "2023-01-01").unwrap();
+        let imported = exported.to_pairings().unwrap();
+        assert!(imported.is_single_sided());
+
+        let standings = pairings.standings().unwrap();
+        let imported_standings = imported.standings().unwrap();
+        for (standing, imported) in standings.iter().zip(imported_standings) {
+            assert_eq!(standing.get_score(), imported.get_score());
+            assert_eq!(
+                standing.get_corp_record().get_games(),
+                imported.get_corp_record().get_games()
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_import() {
+        let mut tournament = NrtmTournament::from_json(SAMPLE).unwrap();
+        tournament.rounds[0][0].player1.runner_score = Some(5);
+        assert_eq!(
+            tournament.to_pairings().unwrap_err(),
+            PairingsError::InvalidImport("unexpected game score 5".to_owned())
+        );
+
+        let mut tournament = NrtmTournament::from_json(SAMPLE).unwrap();
+        let game = tournament.rounds.last_mut().unwrap().first_mut().unwrap();
+        game.player1.role = None;
+        game.player2.role = None;
+        assert_eq!(
+            tournament.to_pairings().unwrap_err(),
+            PairingsError::InvalidImport("elimination table 1 has no sides".to_owned())
+        );
+    }
+}
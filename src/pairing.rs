@@ -1,5 +1,7 @@
 //! Pairing related structures
 
+use std::cmp::Ordering;
+
 use serde::{Deserialize, Serialize};
 
 use crate::Player;
@@ -11,16 +13,169 @@ pub enum Result {
     Draw,
     Loss,
     Bye,
+
+    /// Game drawn by agreement between the players
+    IntentionalDraw,
+
+    /// Game won on agenda points when time was called
+    TimedWin,
+
+    /// Game lost on agenda points when time was called
+    TimedLoss,
+}
+
+impl Result {
+    /// Gets the result from the opponent's point of view
+    #[inline]
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Win => Self::Loss,
+            Self::Draw => Self::Draw,
+            Self::Loss => Self::Win,
+            Self::Bye => Self::Bye,
+            Self::IntentionalDraw => Self::IntentionalDraw,
+            Self::TimedWin => Self::TimedLoss,
+            Self::TimedLoss => Self::TimedWin,
+        }
+    }
+
+    /// Checks if the game was won, including on time
+    #[inline]
+    pub fn is_win(&self) -> bool {
+        matches!(self, Self::Win | Self::TimedWin)
+    }
+
+    /// Checks if the game was lost, including on time
+    #[inline]
+    pub fn is_loss(&self) -> bool {
+        matches!(self, Self::Loss | Self::TimedLoss)
+    }
+
+    /// Checks if the game was drawn, including intentionally
+    #[inline]
+    pub fn is_draw(&self) -> bool {
+        matches!(self, Self::Draw | Self::IntentionalDraw)
+    }
+}
+
+/// Game sides
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    Corp,
+    Runner,
+}
+
+impl Side {
+    /// Gets the side the opponent plays
+    #[inline]
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Corp => Self::Runner,
+            Self::Runner => Self::Corp,
+        }
+    }
+}
+
+/// A single game played in a pairing
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Game {
+    // side played by the pairing's first player, None for byes
+    side: Option<Side>,
+
+    // result from the point of view of the pairing's first player
+    result: Result,
+}
+
+impl Game {
+    /// Creates a new game
+    #[inline]
+    pub(crate) fn new(side: Option<Side>, result: Result) -> Self {
+        Self { side, result }
+    }
+
+    /// Gets the side played by the pairing's first player
+    ///
+    /// This will be None for byes
+    #[inline]
+    pub fn get_side(&self) -> Option<Side> {
+        self.side
+    }
+
+    /// Gets the game result, from the point of view of the pairing's first player
+    #[inline]
+    pub fn get_result(&self) -> Result {
+        self.result
+    }
+
+    /// Gets the game from the point of view of the opponent
+    #[inline]
+    pub fn opposite(&self) -> Self {
+        Self {
+            side: self.side.map(|side| side.opposite()),
+            result: self.result.opposite(),
+        }
+    }
 }
 
 /// A pairing of players for a round
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "PairingData")]
 pub struct Pairing {
     player: Player,
     opponent: Option<Player>,
+
+    // side played by the first player in single-sided pairings
+    player_side: Option<Side>,
+
+    // every game played, in the order they were reported
+    games: Vec<Game>,
+}
+
+/// Serialized pairing, including fields from older formats
+#[derive(Deserialize)]
+struct PairingData {
+    player: Player,
+    opponent: Option<Player>,
+
+    #[serde(default)]
+    player_side: Option<Side>,
+
+    #[serde(default)]
+    games: Vec<Game>,
+
+    // format versions 2 to 4 kept each game's result, with sides implied by their order
+    #[serde(default)]
+    results: Vec<Result>,
+
+    // format version 1 only kept the last reported game result
+    #[serde(default)]
     result: Option<Result>,
 }
 
+impl From<PairingData> for Pairing {
+    fn from(data: PairingData) -> Self {
+        let mut pairing = Self {
+            player: data.player,
+            opponent: data.opponent,
+            player_side: data.player_side,
+            games: data.games,
+        };
+
+        if pairing.games.is_empty() {
+            let mut results = data.results;
+            if results.is_empty() {
+                results.extend(data.result);
+            }
+
+            for result in results {
+                pairing.add_result(result);
+            }
+        }
+
+        pairing
+    }
+}
+
 impl PartialEq for Pairing {
     fn eq(&self, other: &Self) -> bool {
         self.player == other.player && self.opponent == other.opponent
@@ -34,7 +189,19 @@ impl Pairing {
         Self {
             player,
             opponent: Some(opponent),
-            result: None,
+            player_side: None,
+            games: vec![],
+        }
+    }
+
+    /// Creates a new single-sided pairing
+    #[inline]
+    pub(crate) fn new_single_sided(corp: Player, runner: Player) -> Self {
+        Self {
+            player: corp,
+            opponent: Some(runner),
+            player_side: Some(Side::Corp),
+            games: vec![],
         }
     }
 
@@ -44,7 +211,8 @@ impl Pairing {
         Self {
             player,
             opponent: None,
-            result: None,
+            player_side: None,
+            games: vec![],
         }
     }
 
@@ -61,7 +229,8 @@ impl Pairing {
             } else {
                 None
             },
-            result: None,
+            player_side: None,
+            games: vec![],
         }
     }
 
@@ -109,16 +278,113 @@ impl Pairing {
         None
     }
 
-    /// Gets the pairing result
+    /// Gets the side the given player plays in this pairing
     ///
-    /// This will be None if the game result has not been reported
+    /// This will be None for double-sided pairings, byes,
+    /// or if the player is not in this pairing
     #[inline]
-    pub fn get_result(&self) -> Option<Result> {
-        self.result
+    pub fn get_player_side(&self, player: &Player) -> Option<Side> {
+        let side = self.player_side?;
+        if self.player == *player {
+            return Some(side);
+        }
+
+        match &self.opponent {
+            Some(opponent) if *opponent == *player => Some(side.opposite()),
+            _ => None,
+        }
+    }
+
+    /// Gets the side the first player plays in the given game of the pairing, starting from 0
+    ///
+    /// Double-sided pairings play the first game with the first player as the Corp
+    pub(crate) fn get_game_side(&self, game: usize) -> Option<Side> {
+        self.opponent.as_ref()?;
+
+        self.player_side.or(Some(if game.is_multiple_of(2) {
+            Side::Corp
+        } else {
+            Side::Runner
+        }))
+    }
+
+    /// Gets the player who won more games than their opponent
+    ///
+    /// Returns None for byes, ties, and pairings that haven't been reported
+    pub fn get_winner(&self) -> Option<&Player> {
+        let opponent = self.opponent.as_ref()?;
+
+        let wins = self
+            .games
+            .iter()
+            .filter(|game| game.result.is_win())
+            .count();
+        let losses = self
+            .games
+            .iter()
+            .filter(|game| game.result.is_loss())
+            .count();
+        match wins.cmp(&losses) {
+            Ordering::Greater => Some(&self.player),
+            Ordering::Less => Some(opponent),
+            Ordering::Equal => None,
+        }
+    }
+
+    /// Gets every game played, from the point of view of the first player
+    ///
+    /// This will be empty if the game results have not been reported
+    #[inline]
+    pub fn get_games(&self) -> &Vec<Game> {
+        &self.games
+    }
+
+    /// Gets every game played, from the point of view of the given player
+    ///
+    /// This will be empty if the player is not in this pairing
+    pub fn get_player_games(&self, player: &Player) -> Vec<Game> {
+        if self.player == *player {
+            return self.games.clone();
+        }
+
+        match &self.opponent {
+            Some(opponent) if *opponent == *player => {
+                self.games.iter().map(Game::opposite).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Gets the player who played Corp in the given game
+    #[inline]
+    pub fn get_corp(&self, game: &Game) -> Option<&Player> {
+        match game.side? {
+            Side::Corp => Some(&self.player),
+            Side::Runner => self.opponent.as_ref(),
+        }
+    }
+
+    /// Gets the player who played Runner in the given game
+    #[inline]
+    pub fn get_runner(&self, game: &Game) -> Option<&Player> {
+        match game.side? {
+            Side::Corp => self.opponent.as_ref(),
+            Side::Runner => Some(&self.player),
+        }
+    }
+
+    /// Gets each game result, from the point of view of the first player
+    ///
+    /// This will be empty if the game results have not been reported
+    #[inline]
+    pub fn get_results(&self) -> Vec<Result> {
+        self.games.iter().map(|game| game.result).collect()
     }
 
+    /// Adds the result of the next game, played on the side given by the game order
     #[inline]
-    pub(crate) fn update_result(&mut self, result: Result) {
-        self.result = Some(result);
+    pub(crate) fn add_result(&mut self, result: Result) {
+        let side = self.get_game_side(self.games.len());
+        self.games.push(Game::new(side, result));
     }
 }
@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::{Identity, Side};
+
 /// A Netrunner player
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
@@ -12,6 +14,12 @@ pub struct Player {
     first_name: String,
     last_name: String,
     nickname: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    corp_identity: Option<Identity>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    runner_identity: Option<Identity>,
 }
 
 impl PartialEq for Player {
@@ -29,12 +37,37 @@ impl Player {
         nickname: Option<String>,
     ) -> Self {
         let id = Uuid::new_v4();
+        Self::new_with_id(id.to_string(), first_name, last_name, nickname)
+    }
+
+    /// Create a new player with the given internal id
+    ///
+    /// The id must be unique within the tournament
+    #[inline]
+    pub fn new_with_id(
+        id: impl Into<String>,
+        first_name: impl Into<String>,
+        last_name: impl Into<String>,
+        nickname: Option<String>,
+    ) -> Self {
         Self {
-            id: id.to_string(),
+            id: id.into(),
             first_name: first_name.into(),
             last_name: last_name.into(),
             nickname,
+            corp_identity: None,
+            runner_identity: None,
+        }
+    }
+
+    /// Sets the identity the player registered for the given side
+    #[inline]
+    pub fn with_identity(mut self, side: Side, identity: Identity) -> Self {
+        match side {
+            Side::Corp => self.corp_identity = Some(identity),
+            Side::Runner => self.runner_identity = Some(identity),
         }
+        self
     }
 
     /// Get the player's internal id
@@ -68,4 +101,13 @@ impl Player {
             .clone()
             .unwrap_or_else(|| self.get_full_name())
     }
+
+    /// Get the identity the player registered for the given side
+    #[inline]
+    pub fn get_identity(&self, side: Side) -> Option<&Identity> {
+        match side {
+            Side::Corp => self.corp_identity.as_ref(),
+            Side::Runner => self.runner_identity.as_ref(),
+        }
+    }
 }
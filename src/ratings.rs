@@ -0,0 +1,665 @@
+//! Persistent player ratings fed by game results
+//!
+//! Ratings are keyed by player id, so the same player can be followed across a
+//! league's tournaments when registered with the same id in each one, such as with
+//! [`Player::new_with_id`]. Every game updates the players' overall ratings, and
+//! optionally separate Corp and Runner ratings.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Pairing, Pairings, Player, Result, Round, Side};
+
+/// Rating given to new players
+const INITIAL_RATING: f64 = 1500.0;
+
+/// Glicko-2 rating deviation given to new players
+const INITIAL_DEVIATION: f64 = 350.0;
+
+/// Glicko-2 volatility given to new players
+const INITIAL_VOLATILITY: f64 = 0.06;
+
+/// Default Elo K-factor, the most a rating can move in a single game
+const DEFAULT_K_FACTOR: f64 = 32.0;
+
+/// Default Glicko-2 system constant, limiting how quickly volatility changes
+const DEFAULT_TAU: f64 = 0.5;
+
+/// Factor converting between the Glicko and Glicko-2 rating scales
+const GLICKO2_SCALE: f64 = 173.7178;
+
+/// Convergence tolerance of the Glicko-2 volatility calculation
+const GLICKO2_TOLERANCE: f64 = 0.000_001;
+
+/// Rating algorithms
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RatingSystem {
+    /// Elo ratings, moving by at most the K-factor each game
+    Elo { k_factor: f64 },
+
+    /// Glicko-2 ratings, which also track how reliable each rating is
+    Glicko2 { tau: f64 },
+}
+
+/// A player's rating
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    rating: f64,
+
+    // Glicko-2 only
+    deviation: f64,
+    volatility: f64,
+
+    games: u64,
+}
+
+impl Default for Rating {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            rating: INITIAL_RATING,
+            deviation: INITIAL_DEVIATION,
+            volatility: INITIAL_VOLATILITY,
+            games: 0,
+        }
+    }
+}
+
+impl Rating {
+    /// Gets the rating
+    #[inline]
+    pub fn get_rating(&self) -> f64 {
+        self.rating
+    }
+
+    /// Gets the Glicko-2 rating deviation, where lower values are more reliable
+    ///
+    /// Elo ratings always have the initial deviation
+    #[inline]
+    pub fn get_deviation(&self) -> f64 {
+        self.deviation
+    }
+
+    /// Gets the Glicko-2 volatility, the expected fluctuation of the rating
+    #[inline]
+    pub fn get_volatility(&self) -> f64 {
+        self.volatility
+    }
+
+    /// Gets the number of rated games played
+    #[inline]
+    pub fn get_games(&self) -> u64 {
+        self.games
+    }
+}
+
+/// Every rating kept for a player
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct PlayerRatings {
+    overall: Rating,
+    corp: Rating,
+    runner: Rating,
+}
+
+impl PlayerRatings {
+    fn get(&self, side: Option<Side>) -> Rating {
+        match side {
+            None => self.overall,
+            Some(Side::Corp) => self.corp,
+            Some(Side::Runner) => self.runner,
+        }
+    }
+
+    fn get_mut(&mut self, side: Option<Side>) -> &mut Rating {
+        match side {
+            None => &mut self.overall,
+            Some(Side::Corp) => &mut self.corp,
+            Some(Side::Runner) => &mut self.runner,
+        }
+    }
+}
+
+/// A rating that a game updates: the player's id and the side rated, or None for overall
+type RatingKey<'a> = (&'a str, Option<Side>);
+
+/// Gets a game's score for the Elo and Glicko-2 calculations
+fn score(result: Result) -> Option<f64> {
+    if result.is_win() {
+        Some(1.0)
+    } else if result.is_loss() {
+        Some(0.0)
+    } else if result.is_draw() {
+        Some(0.5)
+    } else {
+        None
+    }
+}
+
+/// Reduces the impact of a game against an opponent with an unreliable rating
+#[inline]
+fn glicko2_g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+}
+
+/// Gets the expected score against an opponent on the Glicko-2 scale
+#[inline]
+fn glicko2_expected(mu: f64, opponent_mu: f64, opponent_phi: f64) -> f64 {
+    1.0 / (1.0 + (-glicko2_g(opponent_phi) * (mu - opponent_mu)).exp())
+}
+
+/// Calculates the new Glicko-2 volatility with the Illinois algorithm
+fn glicko2_volatility(phi: f64, sigma: f64, delta: f64, v: f64, tau: f64) -> f64 {
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        let d = phi * phi + v + ex;
+        ex * (delta * delta - phi * phi - v - ex) / (2.0 * d * d) - (x - a) / (tau * tau)
+    };
+
+    let mut upper = a;
+    let mut lower = if delta * delta > phi * phi + v {
+        (delta * delta - phi * phi - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * tau) < 0.0 {
+            k += 1.0;
+        }
+        a - k * tau
+    };
+
+    let (mut f_upper, mut f_lower) = (f(upper), f(lower));
+    while (lower - upper).abs() > GLICKO2_TOLERANCE {
+        let next = upper + (upper - lower) * f_upper / (f_lower - f_upper);
+        let f_next = f(next);
+        if f_next * f_lower <= 0.0 {
+            upper = lower;
+            f_upper = f_lower;
+        } else {
+            f_upper /= 2.0;
+        }
+        lower = next;
+        f_lower = f_next;
+    }
+    (upper / 2.0).exp()
+}
+
+/// Player ratings for a league, updated from each reported game
+///
+/// Each call that adds games is a single rating period: every game is rated
+/// against the ratings from before the period. With Glicko-2, every rated player
+/// who didn't play in the period has their rating deviation raised
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ratings {
+    system: RatingSystem,
+    separate_sides: bool,
+
+    // ratings by player id
+    players: HashMap<String, PlayerRatings>,
+}
+
+impl Ratings {
+    /// Creates Elo ratings with a K-factor of 32
+    #[inline]
+    pub fn new_elo() -> Self {
+        Self::new(RatingSystem::Elo {
+            k_factor: DEFAULT_K_FACTOR,
+        })
+    }
+
+    /// Creates Glicko-2 ratings with a system constant of 0.5
+    #[inline]
+    pub fn new_glicko2() -> Self {
+        Self::new(RatingSystem::Glicko2 { tau: DEFAULT_TAU })
+    }
+
+    /// Creates ratings using the given system
+    #[inline]
+    pub fn new(system: RatingSystem) -> Self {
+        Self {
+            system,
+            separate_sides: false,
+            players: HashMap::new(),
+        }
+    }
+
+    /// Keeps separate Corp and Runner ratings as well as the overall rating
+    ///
+    /// Side ratings are only updated from games with a recorded side
+    #[inline]
+    pub fn with_separate_sides(mut self) -> Self {
+        self.separate_sides = true;
+        self
+    }
+
+    /// Gets the rating system
+    #[inline]
+    pub fn get_system(&self) -> RatingSystem {
+        self.system
+    }
+
+    /// Checks if separate Corp and Runner ratings are kept
+    #[inline]
+    pub fn has_separate_sides(&self) -> bool {
+        self.separate_sides
+    }
+
+    /// Gets a player's overall rating, which is the initial rating for unrated players
+    pub fn get_rating(&self, player: &Player) -> Rating {
+        self.get((player.get_id(), None))
+    }
+
+    /// Gets a player's rating for a side, or None if separate side ratings aren't kept
+    pub fn get_side_rating(&self, player: &Player, side: Side) -> Option<Rating> {
+        if !self.separate_sides {
+            return None;
+        }
+
+        Some(self.get((player.get_id(), Some(side))))
+    }
+
+    /// Gets the id and overall rating of every rated player, highest rated first
+    pub fn get_rated_players(&self) -> Vec<(&String, Rating)> {
+        let mut players = self
+            .players
+            .iter()
+            .map(|(id, ratings)| (id, ratings.overall))
+            .collect::<Vec<_>>();
+        players.sort_by(|(id, rating), (other_id, other)| {
+            other
+                .rating
+                .total_cmp(&rating.rating)
+                .then(id.cmp(other_id))
+        });
+        players
+    }
+
+    /// Compares players by overall rating, highest first, as a tiebreak
+    #[inline]
+    pub fn compare(&self, player: &Player, other: &Player) -> Ordering {
+        self.get_rating(other)
+            .rating
+            .total_cmp(&self.get_rating(player).rating)
+    }
+
+    /// Sorts players by overall rating, highest first, such as to seed first round pairings
+    pub fn seed(&self, players: &[Player]) -> Vec<Player> {
+        let mut players = players.to_vec();
+        players.sort_by(|player, other| self.compare(player, other));
+        players
+    }
+
+    /// Updates ratings from every game played in a pairing, as a single rating period
+    ///
+    /// Byes and unreported pairings are ignored
+    #[inline]
+    pub fn add_pairing(&mut self, pairing: &Pairing) {
+        self.add_pairings([pairing]);
+    }
+
+    /// Updates ratings from every game played in a round, as a single rating period
+    #[inline]
+    pub fn add_round(&mut self, round: &Round) {
+        self.add_pairings(round.get_pairings());
+    }
+
+    /// Replays a tournament's history, rating each Swiss and elimination round in order
+    pub fn add_tournament(&mut self, pairings: &Pairings) {
+        let elimination = pairings
+            .get_elimination()
+            .map(|elimination| elimination.get_rounds().as_slice())
+            .unwrap_or_default();
+        for round in pairings.get_rounds().iter().chain(elimination) {
+            self.add_round(round);
+        }
+    }
+
+    fn add_pairings<'a>(&mut self, pairings: impl IntoIterator<Item = &'a Pairing>) {
+        // each game from both players' point of view
+        let mut games = vec![];
+        for pairing in pairings {
+            let Some(opponent) = pairing.get_opponent() else {
+                continue;
+            };
+            let (player, opponent) = (pairing.get_player().get_id(), opponent.get_id());
+            for game in pairing.get_games() {
+                let Some(score) = score(game.get_result()) else {
+                    continue;
+                };
+                games.push(((player.as_str(), None), (opponent.as_str(), None), score));
+                games.push((
+                    (opponent.as_str(), None),
+                    (player.as_str(), None),
+                    1.0 - score,
+                ));
+
+                if let (true, Some(side)) = (self.separate_sides, game.get_side()) {
+                    let (player, opponent) = (
+                        (player.as_str(), Some(side)),
+                        (opponent.as_str(), Some(side.opposite())),
+                    );
+                    games.push((player, opponent, score));
+                    games.push((opponent, player, 1.0 - score));
+                }
+            }
+        }
+
+        let updated = match self.system {
+            RatingSystem::Elo { k_factor } => self.rate_elo(&games, k_factor),
+            RatingSystem::Glicko2 { tau } => {
+                let updated = self.rate_glicko2(&games, tau);
+                if !games.is_empty() {
+                    self.glicko2_inactive(&updated);
+                }
+                updated
+            }
+        };
+        for ((player, side), rating) in updated {
+            *self
+                .players
+                .entry(player.to_owned())
+                .or_default()
+                .get_mut(side) = rating;
+        }
+    }
+
+    /// Gets the rating for a player's side, or their overall rating
+    fn get(&self, (player, side): RatingKey) -> Rating {
+        self.players
+            .get(player)
+            .map(|ratings| ratings.get(side))
+            .unwrap_or_default()
+    }
+
+    /// Raises the rating deviation of every rating not updated in a Glicko-2 rating period
+    fn glicko2_inactive(&mut self, updated: &HashMap<RatingKey, Rating>) {
+        let sides: &[_] = if self.separate_sides {
+            &[None, Some(Side::Corp), Some(Side::Runner)]
+        } else {
+            &[None]
+        };
+        for (player, ratings) in &mut self.players {
+            for side in sides {
+                let rating = ratings.get_mut(*side);
+                if rating.games == 0 || updated.contains_key(&(player.as_str(), *side)) {
+                    continue;
+                }
+                let phi = rating.deviation / GLICKO2_SCALE;
+                let phi = (phi * phi + rating.volatility * rating.volatility).sqrt();
+                rating.deviation = phi * GLICKO2_SCALE;
+            }
+        }
+    }
+
+    fn rate_elo<'a>(
+        &self,
+        games: &[(RatingKey<'a>, RatingKey<'a>, f64)],
+        k_factor: f64,
+    ) -> HashMap<RatingKey<'a>, Rating> {
+        let mut updated = HashMap::new();
+        for (player, opponent, score) in games {
+            let (rating, opponent) = (self.get(*player), self.get(*opponent));
+            let expected = 1.0 / (1.0 + 10f64.powf((opponent.rating - rating.rating) / 400.0));
+
+            let updated = updated.entry(*player).or_insert(rating);
+            updated.rating += k_factor * (score - expected);
+            updated.games += 1;
+        }
+        updated
+    }
+
+    fn rate_glicko2<'a>(
+        &self,
+        games: &[(RatingKey<'a>, RatingKey<'a>, f64)],
+        tau: f64,
+    ) -> HashMap<RatingKey<'a>, Rating> {
+        // the opponents' scaled ratings and scores of each player's games
+        let mut results = HashMap::<_, Vec<_>>::new();
+        for (player, opponent, score) in games {
+            let opponent = self.get(*opponent);
+            results.entry(*player).or_default().push((
+                (opponent.rating - INITIAL_RATING) / GLICKO2_SCALE,
+                opponent.deviation / GLICKO2_SCALE,
+                *score,
+            ));
+        }
+
+        results
+            .into_iter()
+            .map(|(player, results)| {
+                let rating = self.get(player);
+                let mu = (rating.rating - INITIAL_RATING) / GLICKO2_SCALE;
+                let phi = rating.deviation / GLICKO2_SCALE;
+
+                let mut variance = 0.0;
+                let mut improvement = 0.0;
+                for (opponent_mu, opponent_phi, score) in &results {
+                    let g = glicko2_g(*opponent_phi);
+                    let expected = glicko2_expected(mu, *opponent_mu, *opponent_phi);
+                    variance += g * g * expected * (1.0 - expected);
+                    improvement += g * (score - expected);
+                }
+                let v = 1.0 / variance;
+
+                let volatility =
+                    glicko2_volatility(phi, rating.volatility, v * improvement, v, tau);
+                let phi_star = (phi * phi + volatility * volatility).sqrt();
+                let phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+                let mu = mu + phi * phi * improvement;
+
+                let rating = Rating {
+                    rating: mu * GLICKO2_SCALE + INITIAL_RATING,
+                    deviation: phi * GLICKO2_SCALE,
+                    volatility,
+                    games: rating.games + results.len() as u64,
+                };
+                (player, rating)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(count: usize) -> Vec<Player> {
+        (0..count)
+            .map(|idx| Player::new("first", idx.to_string(), None))
+            .collect()
+    }
+
+    /// Plays a Swiss round where the first player at each table wins both games
+    fn play_round(pairings: &mut Pairings) {
+        let round = pairings.next_round().unwrap();
+        let results = round
+            .iter()
+            .flat_map(|pairing| [(pairing, Result::Win), (pairing, Result::Win)])
+            .collect::<Vec<_>>();
+        pairings.round_ended(results).unwrap();
+    }
+
+    #[test]
+    fn elo() {
+        let players = players(2);
+        let mut pairings = Pairings::new_swiss().with_seed(0);
+        for player in &players {
+            pairings.register_player(player.clone()).unwrap();
+        }
+        play_round(&mut pairings);
+        let pairing = &pairings.get_rounds()[0].get_pairings()[0];
+        let (winner, loser) = (
+            pairing.get_player(),
+            pairing.get_opponent().as_ref().unwrap(),
+        );
+
+        let mut ratings = Ratings::new_elo();
+        ratings.add_pairing(pairing);
+        assert_eq!(ratings.get_rating(winner).get_rating(), 1532.0);
+        assert_eq!(ratings.get_rating(loser).get_rating(), 1468.0);
+        assert_eq!(ratings.get_rating(winner).get_games(), 2);
+        assert_eq!(ratings.get_side_rating(winner, Side::Corp), None);
+
+        // the favourite gains less from beating the same player again
+        ratings.add_pairing(pairing);
+        let gain = ratings.get_rating(winner).get_rating() - 1532.0;
+        assert!(gain > 0.0 && gain < 32.0);
+
+        let rated = ratings.get_rated_players();
+        assert_eq!(rated.len(), 2);
+        assert_eq!(rated[0], (winner.get_id(), ratings.get_rating(winner)));
+        assert_eq!(ratings.seed(&players)[0], *winner);
+        assert_eq!(ratings.compare(winner, loser), Ordering::Less);
+        assert_eq!(
+            ratings.get_rating(&Player::new("first", "last", None)),
+            Rating::default()
+        );
+    }
+
+    #[test]
+    fn glicko2() {
+        // the worked example from the Glicko-2 paper
+        let mut ratings = Ratings::new_glicko2();
+        let player = ("player", None);
+        ratings.players.insert(
+            player.0.to_owned(),
+            PlayerRatings {
+                overall: Rating {
+                    rating: 1500.0,
+                    deviation: 200.0,
+                    ..Rating::default()
+                },
+                ..PlayerRatings::default()
+            },
+        );
+        for (idx, (rating, deviation)) in [(1400.0, 30.0), (1550.0, 100.0), (1700.0, 300.0)]
+            .into_iter()
+            .enumerate()
+        {
+            ratings.players.insert(
+                idx.to_string(),
+                PlayerRatings {
+                    overall: Rating {
+                        rating,
+                        deviation,
+                        ..Rating::default()
+                    },
+                    ..PlayerRatings::default()
+                },
+            );
+        }
+
+        let opponents = ["0", "1", "2"].map(|id| (id, None));
+        let games = [
+            (player, opponents[0], 1.0),
+            (player, opponents[1], 0.0),
+            (player, opponents[2], 0.0),
+        ];
+        let rating = ratings.rate_glicko2(&games, 0.5)[&player];
+        assert!((rating.get_rating() - 1464.06).abs() < 0.01);
+        assert!((rating.get_deviation() - 151.52).abs() < 0.01);
+        assert!((rating.get_volatility() - 0.05999).abs() < 0.00001);
+        assert_eq!(rating.get_games(), 3);
+    }
+
+    #[test]
+    fn glicko2_inactivity() {
+        let players = players(4);
+        let mut pairings = Pairings::new_swiss().with_seed(0);
+        for player in &players {
+            pairings.register_player(player.clone()).unwrap();
+        }
+        play_round(&mut pairings);
+        let round = &pairings.get_rounds()[0];
+
+        let mut ratings = Ratings::new_glicko2().with_separate_sides();
+        ratings.add_round(round);
+        let (played, absent) = (&round.get_pairings()[0], &round.get_pairings()[1]);
+        let before = ratings.get_rating(absent.get_player());
+        let corp = ratings.get_side_rating(absent.get_player(), Side::Corp);
+
+        // a player who sits out a rating period is less reliably rated
+        ratings.add_pairing(played);
+        let after = ratings.get_rating(absent.get_player());
+        let phi = before.get_deviation() / GLICKO2_SCALE;
+        let expected = (phi * phi + before.get_volatility().powi(2)).sqrt() * GLICKO2_SCALE;
+        assert!((after.get_deviation() - expected).abs() < 1e-9);
+        assert!(after.get_deviation() > before.get_deviation());
+        assert_eq!(after.get_rating(), before.get_rating());
+        assert_eq!(after.get_games(), before.get_games());
+        assert!(
+            ratings
+                .get_side_rating(absent.get_player(), Side::Corp)
+                .unwrap()
+                .get_deviation()
+                > corp.unwrap().get_deviation()
+        );
+
+        // a period without any games doesn't count
+        let mut bye = Pairings::new_swiss();
+        bye.register_player(Player::new("first", "last", None))
+            .unwrap();
+        ratings.add_tournament(&bye);
+        assert_eq!(ratings.get_rating(absent.get_player()), after);
+    }
+
+    #[test]
+    fn league() {
+        // players registered with the same id in separately created events
+        let league = (0..4)
+            .map(|idx| Player::new_with_id(format!("league-{idx}"), "first", "last", None))
+            .collect::<Vec<_>>();
+
+        let mut ratings = Ratings::new_elo();
+        for seed in 0..2 {
+            let mut pairings = Pairings::new_swiss().with_seed(seed);
+            for player in &league {
+                pairings.register_player(player.clone()).unwrap();
+            }
+            play_round(&mut pairings);
+            ratings.add_tournament(&pairings);
+        }
+
+        assert_eq!(ratings.get_rated_players().len(), 4);
+        for player in &league {
+            assert_eq!(ratings.get_rating(player).get_games(), 4);
+        }
+    }
+
+    #[test]
+    fn replay_tournament() {
+        let players = players(8);
+        let mut pairings = Pairings::new_swiss().with_seed(0);
+        for player in &players {
+            pairings.register_player(player.clone()).unwrap();
+        }
+        for _ in 0..3 {
+            play_round(&mut pairings);
+        }
+
+        let mut ratings = Ratings::new_glicko2().with_separate_sides();
+        ratings.add_tournament(&pairings);
+
+        // the only player to win every round is rated highest
+        let standings = pairings.standings().unwrap();
+        let leader = standings[0].get_player();
+        assert_eq!(ratings.get_rated_players()[0].0, leader.get_id());
+
+        let rating = ratings.get_rating(leader);
+        assert_eq!(rating.get_games(), 6);
+        assert!(rating.get_deviation() < INITIAL_DEVIATION);
+
+        let corp = ratings.get_side_rating(leader, Side::Corp).unwrap();
+        let runner = ratings.get_side_rating(leader, Side::Runner).unwrap();
+        assert_eq!(corp.get_games(), 3);
+        assert_eq!(runner.get_games(), 3);
+        assert!(corp.get_rating() > INITIAL_RATING);
+
+        let restored: Ratings =
+            serde_json::from_str(&serde_json::to_string(&ratings).unwrap()).unwrap();
+        assert_eq!(restored.get_rated_players().len(), 8);
+        assert!((restored.get_rating(leader).get_rating() - rating.get_rating()).abs() < 1e-9);
+    }
+}
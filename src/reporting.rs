@@ -0,0 +1,469 @@
+//! Player self-reporting of round results
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Pairing, PairingsError, Player, Result, ResultsError, Round};
+
+/// State of a pairing's reported results
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportStatus {
+    /// One or both players have yet to report
+    Pending,
+
+    /// Both players agree on the results, or a TO has set them
+    ///
+    /// Contains each game's result from the point of view of the pairing's first player
+    Confirmed(Vec<Result>),
+
+    /// The players' reports disagree and a judge needs to resolve them
+    Disputed,
+}
+
+/// Results reported for a single pairing,
+/// each from the point of view of the pairing's first player
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct PairingReports {
+    player: Option<Vec<Result>>,
+    opponent: Option<Vec<Result>>,
+
+    // set by a TO, replacing both players' reports
+    judge: Option<Vec<Result>>,
+}
+
+impl PairingReports {
+    fn get_status(&self) -> ReportStatus {
+        if let Some(results) = &self.judge {
+            return ReportStatus::Confirmed(results.clone());
+        }
+
+        match (&self.player, &self.opponent) {
+            (Some(player), Some(opponent)) if player == opponent => {
+                ReportStatus::Confirmed(player.clone())
+            }
+            (Some(_), Some(_)) => ReportStatus::Disputed,
+            _ => ReportStatus::Pending,
+        }
+    }
+}
+
+/// Results reported by the players of each pairing in a round
+///
+/// Both players in a pairing report every game of their match, and the results are
+/// confirmed once their reports agree. Disagreements are flagged for a judge,
+/// and a TO can override either report.
+///
+/// Reports can only be used with the round they were created for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundReports {
+    // number of the round, starting from 1
+    round: usize,
+
+    games_per_round: usize,
+
+    // ids of each pairing's players, in the round's order
+    players: Vec<(String, Option<String>)>,
+
+    // reports for each of the round's pairings, in the same order
+    reports: Vec<PairingReports>,
+}
+
+/// Gets the ids of each pairing's players
+fn pairing_players(round: &Round) -> Vec<(String, Option<String>)> {
+    round
+        .get_pairings()
+        .iter()
+        .map(|pairing| {
+            (
+                pairing.get_player().get_id().clone(),
+                pairing
+                    .get_opponent()
+                    .as_ref()
+                    .map(|opponent| opponent.get_id().clone()),
+            )
+        })
+        .collect()
+}
+
+impl RoundReports {
+    /// Creates empty reports for the given round, numbered from 1
+    #[inline]
+    pub(crate) fn new(number: usize, round: &Round, games_per_round: usize) -> Self {
+        Self {
+            round: number,
+            games_per_round,
+            players: pairing_players(round),
+            reports: vec![PairingReports::default(); round.get_pairings().len()],
+        }
+    }
+
+    /// Gets the number of the round the reports are for, starting from 1
+    #[inline]
+    pub fn get_round(&self) -> usize {
+        self.round
+    }
+
+    /// Gets the number of games each pairing reports
+    #[inline]
+    pub fn get_games_per_round(&self) -> usize {
+        self.games_per_round
+    }
+
+    /// Checks if the reports were created for the round's pairings
+    fn is_for(&self, round: &Round) -> bool {
+        self.players == pairing_players(round)
+    }
+
+    /// Ensures the reports were created for the round's pairings
+    fn check_round(&self, round: &Round) -> std::result::Result<(), PairingsError> {
+        if self.is_for(round) {
+            Ok(())
+        } else {
+            Err(PairingsError::WrongRoundReports)
+        }
+    }
+
+    /// Finds the player's pairing, checking the results are valid for it
+    ///
+    /// The results are turned to the point of view of the pairing's first player
+    fn find_pairing<'a>(
+        &self,
+        round: &'a Round,
+        player: &Player,
+        results: Vec<Result>,
+    ) -> std::result::Result<(usize, &'a Pairing, Vec<Result>), PairingsError> {
+        if round.has_ended() {
+            return Err(PairingsError::RoundAlreadyEnded);
+        }
+        self.check_round(round)?;
+
+        let (idx, pairing) = round
+            .get_pairings()
+            .iter()
+            .enumerate()
+            .find(|(_, pairing)| pairing.has_player(player))
+            .ok_or_else(|| PairingsError::UnknownPlayer(player.get_id().clone()))?;
+
+        let first = pairing.get_player().get_id().clone();
+        if pairing.get_opponent().is_none() || results.contains(&Result::Bye) {
+            return Err(ResultsError::InvalidBye(first).into());
+        }
+        if results.len() != self.games_per_round {
+            return Err(ResultsError::GameCount {
+                player: first,
+                expected: self.games_per_round,
+                actual: results.len(),
+            }
+            .into());
+        }
+
+        let results = if pairing.get_player() == player {
+            results
+        } else {
+            results.iter().map(Result::opposite).collect()
+        };
+        Ok((idx, pairing, results))
+    }
+
+    /// Records a player's report of their match, replacing any earlier report from them
+    ///
+    /// Each game's result is from the point of view of the reporting player,
+    /// in the same order as results are given to `round_ended`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the round has ended or the reports are for another round,
+    /// the player isn't in the round or has a bye, or the results have the wrong number of games
+    pub fn report(
+        &mut self,
+        round: &Round,
+        player: &Player,
+        results: Vec<Result>,
+    ) -> std::result::Result<ReportStatus, PairingsError> {
+        let (idx, pairing, results) = self.find_pairing(round, player, results)?;
+
+        let reports = &mut self.reports[idx];
+        if pairing.get_player() == player {
+            reports.player = Some(results);
+        } else {
+            reports.opponent = Some(results);
+        }
+
+        Ok(reports.get_status())
+    }
+
+    /// Sets the results of a player's match as a TO, overriding both players' reports
+    ///
+    /// Each game's result is from the point of view of the given player
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the round has ended or the reports are for another round,
+    /// the player isn't in the round or has a bye, or the results have the wrong number of games
+    pub fn override_results(
+        &mut self,
+        round: &Round,
+        player: &Player,
+        results: Vec<Result>,
+    ) -> std::result::Result<(), PairingsError> {
+        let (idx, _, results) = self.find_pairing(round, player, results)?;
+        self.reports[idx].judge = Some(results);
+        Ok(())
+    }
+
+    /// Gets the status of a player's match,
+    /// or None if they aren't in the round or the reports are for another round
+    ///
+    /// Byes are always confirmed
+    pub fn get_status(&self, round: &Round, player: &Player) -> Option<ReportStatus> {
+        let idx = round
+            .get_pairings()
+            .iter()
+            .position(|pairing| pairing.has_player(player))?;
+        self.get_pairing_status(round, idx)
+    }
+
+    fn get_pairing_status(&self, round: &Round, idx: usize) -> Option<ReportStatus> {
+        if !self.is_for(round) {
+            return None;
+        }
+
+        let pairing = round.get_pairings().get(idx)?;
+        if pairing.get_opponent().is_none() {
+            return Some(ReportStatus::Confirmed(vec![
+                Result::Bye;
+                self.games_per_round
+            ]));
+        }
+        Some(self.reports.get(idx)?.get_status())
+    }
+
+    /// Gets the pairings whose reports disagree
+    pub fn get_disputed<'a>(&self, round: &'a Round) -> Vec<&'a Pairing> {
+        round
+            .get_pairings()
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| self.get_pairing_status(round, *idx) == Some(ReportStatus::Disputed))
+            .map(|(_, pairing)| pairing)
+            .collect()
+    }
+
+    /// Checks if every pairing in the round has confirmed results
+    pub fn is_confirmed(&self, round: &Round) -> bool {
+        (0..round.get_pairings().len()).all(|idx| {
+            matches!(
+                self.get_pairing_status(round, idx),
+                Some(ReportStatus::Confirmed(_))
+            )
+        })
+    }
+
+    /// Gets the confirmed results of every pairing, ready to end the round with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reports are for another round,
+    /// or if any pairing is still pending or disputed
+    pub fn get_confirmed_results<'a>(
+        &self,
+        round: &'a Round,
+    ) -> std::result::Result<Vec<(&'a Pairing, Result)>, PairingsError> {
+        self.check_round(round)?;
+
+        let mut results = vec![];
+        for (idx, pairing) in round.get_pairings().iter().enumerate() {
+            match self.get_pairing_status(round, idx) {
+                Some(ReportStatus::Confirmed(games)) => {
+                    results.extend(games.into_iter().map(|result| (pairing, result)))
+                }
+                _ => {
+                    return Err(
+                        ResultsError::Unconfirmed(pairing.get_player().get_id().clone()).into(),
+                    )
+                }
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pairings;
+
+    use super::*;
+
+    fn pairings(players: usize) -> Pairings {
+        let mut pairings = Pairings::new_swiss().with_seed(0);
+        for idx in 0..players {
+            pairings
+                .register_player(Player::new("first", format!("last{}", idx), None))
+                .unwrap();
+        }
+        pairings.next_round().unwrap();
+        pairings
+    }
+
+    fn players(pairings: &Pairings, idx: usize) -> (Player, Player) {
+        let pairing = &pairings.get_rounds().last().unwrap().get_pairings()[idx];
+        (
+            pairing.get_player().clone(),
+            pairing.get_opponent().clone().unwrap(),
+        )
+    }
+
+    #[test]
+    fn agreement() {
+        let mut pairings = pairings(4);
+        let mut reports = pairings.new_round_reports().unwrap();
+        let round = pairings.get_rounds().last().unwrap();
+
+        let (player, opponent) = players(&pairings, 0);
+        assert_eq!(
+            reports.report(round, &player, vec![Result::Win, Result::Loss]),
+            Ok(ReportStatus::Pending)
+        );
+        assert_eq!(
+            reports.get_status(round, &opponent),
+            Some(ReportStatus::Pending)
+        );
+
+        // the opponent reports from their own point of view
+        assert_eq!(
+            reports.report(round, &opponent, vec![Result::Loss, Result::Win]),
+            Ok(ReportStatus::Confirmed(vec![Result::Win, Result::Loss]))
+        );
+        assert!(!reports.is_confirmed(round));
+        assert!(matches!(
+            reports.get_confirmed_results(round),
+            Err(PairingsError::InvalidResults(ResultsError::Unconfirmed(_)))
+        ));
+
+        let (player, opponent) = players(&pairings, 1);
+        reports
+            .report(round, &player, vec![Result::Draw, Result::Draw])
+            .unwrap();
+        reports
+            .report(round, &opponent, vec![Result::Draw, Result::Draw])
+            .unwrap();
+        assert!(reports.is_confirmed(round));
+
+        assert_eq!(reports.get_confirmed_results(round).unwrap().len(), 4);
+        pairings.round_ended_from_reports(&reports).unwrap();
+        assert!(pairings.get_rounds().last().unwrap().has_ended());
+    }
+
+    #[test]
+    fn dispute() {
+        let pairings = pairings(2);
+        let mut reports = pairings.new_round_reports().unwrap();
+        let round = pairings.get_rounds().last().unwrap();
+
+        let (player, opponent) = players(&pairings, 0);
+        reports
+            .report(round, &player, vec![Result::Win, Result::Win])
+            .unwrap();
+        assert_eq!(
+            reports.report(round, &opponent, vec![Result::Win, Result::Loss]),
+            Ok(ReportStatus::Disputed)
+        );
+        assert_eq!(reports.get_disputed(round).len(), 1);
+
+        // correcting a report resolves the dispute
+        assert_eq!(
+            reports.report(round, &opponent, vec![Result::Loss, Result::Loss]),
+            Ok(ReportStatus::Confirmed(vec![Result::Win, Result::Win]))
+        );
+        assert!(reports.get_disputed(round).is_empty());
+
+        // a TO override replaces both reports
+        reports
+            .override_results(round, &opponent, vec![Result::Win, Result::Loss])
+            .unwrap();
+        assert_eq!(
+            reports.get_status(round, &player),
+            Some(ReportStatus::Confirmed(vec![Result::Loss, Result::Win]))
+        );
+    }
+
+    #[test]
+    fn bye() {
+        let pairings = pairings(3);
+        let mut reports = pairings.new_round_reports().unwrap();
+        let round = pairings.get_rounds().last().unwrap();
+
+        let bye = round
+            .get_pairings()
+            .iter()
+            .find(|pairing| pairing.get_opponent().is_none())
+            .unwrap()
+            .get_player()
+            .clone();
+        assert_eq!(
+            reports.get_status(round, &bye),
+            Some(ReportStatus::Confirmed(vec![Result::Bye, Result::Bye]))
+        );
+        assert!(matches!(
+            reports.report(round, &bye, vec![Result::Win, Result::Win]),
+            Err(PairingsError::InvalidResults(ResultsError::InvalidBye(_)))
+        ));
+    }
+
+    #[test]
+    fn stale_reports() {
+        let mut pairings = pairings(4);
+        let mut reports = pairings.new_round_reports().unwrap();
+        let round = pairings.get_rounds().last().unwrap();
+        for idx in 0..2 {
+            let (player, opponent) = players(&pairings, idx);
+            reports
+                .report(round, &player, vec![Result::Win, Result::Win])
+                .unwrap();
+            reports
+                .report(round, &opponent, vec![Result::Loss, Result::Loss])
+                .unwrap();
+        }
+        pairings.round_ended_from_reports(&reports).unwrap();
+
+        // the first round's reports can't be used for the second round
+        pairings.next_round().unwrap();
+        let round = pairings.get_rounds().last().unwrap();
+        let (player, _) = players(&pairings, 0);
+        assert!(!reports.is_confirmed(round));
+        assert_eq!(reports.get_status(round, &player), None);
+        assert_eq!(
+            reports.report(round, &player, vec![Result::Win, Result::Win]),
+            Err(PairingsError::WrongRoundReports)
+        );
+        assert_eq!(
+            reports.get_confirmed_results(round).unwrap_err(),
+            PairingsError::WrongRoundReports
+        );
+        assert_eq!(
+            pairings.round_ended_from_reports(&reports),
+            Err(PairingsError::WrongRoundReports)
+        );
+        assert!(!pairings.get_rounds().last().unwrap().has_ended());
+    }
+
+    #[test]
+    fn invalid_reports() {
+        let pairings = pairings(2);
+        let mut reports = pairings.new_round_reports().unwrap();
+        let round = pairings.get_rounds().last().unwrap();
+
+        let (player, _) = players(&pairings, 0);
+        assert!(matches!(
+            reports.report(round, &player, vec![Result::Win]),
+            Err(PairingsError::InvalidResults(
+                ResultsError::GameCount { .. }
+            ))
+        ));
+
+        let stranger = Player::new("first", "last", None);
+        assert!(matches!(
+            reports.report(round, &stranger, vec![Result::Win, Result::Win]),
+            Err(PairingsError::UnknownPlayer(_))
+        ));
+    }
+}
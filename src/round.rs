@@ -5,9 +5,12 @@ use serde::{Deserialize, Serialize};
 use crate::{Pairing, Result};
 
 /// Game round container
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Round {
     pairings: Vec<Pairing>,
+
+    #[serde(default)]
+    ended: bool,
 }
 
 impl Round {
@@ -16,12 +19,19 @@ impl Round {
     pub(crate) fn new(pairings: impl Into<Vec<Pairing>>) -> Self {
         Self {
             pairings: pairings.into(),
+            ended: false,
         }
     }
 
+    /// Checks if the round results have been reported
+    #[inline]
+    pub fn has_ended(&self) -> bool {
+        self.ended
+    }
+
     /// Get the round's pairings
     #[inline]
-    pub(crate) fn get_pairings(&self) -> &Vec<Pairing> {
+    pub fn get_pairings(&self) -> &Vec<Pairing> {
         &self.pairings
     }
 
@@ -33,10 +43,12 @@ impl Round {
         for (result_pairing, result) in results.as_ref() {
             for pairing in &mut self.pairings {
                 if **result_pairing == *pairing {
-                    pairing.update_result(*result);
+                    pairing.add_result(*result);
                     break;
                 }
             }
         }
+
+        self.ended = true;
     }
 }
@@ -0,0 +1,174 @@
+//! Swiss scoring systems
+
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// Points awarded for each game result in Swiss rounds
+///
+/// The default is the standard NSG scoring of 3 points for a win,
+/// 1 for a draw and 0 for a loss, with a bye worth a full round win.
+/// Intentional draws score as draws, and timed wins as wins.
+///
+/// When deserializing, missing points follow the same rules as `ScoringSystem::new`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ScoringSystemData")]
+pub struct ScoringSystem {
+    win: u64,
+    draw: u64,
+    loss: u64,
+
+    // points for each game of a bye
+    bye: u64,
+
+    intentional_draw: u64,
+
+    // games decided on agenda points when time was called
+    timed_win: u64,
+    timed_loss: u64,
+}
+
+/// Serialized scoring system, where any of the points may be missing
+#[derive(Deserialize)]
+struct ScoringSystemData {
+    win: Option<u64>,
+    draw: Option<u64>,
+    loss: Option<u64>,
+    bye: Option<u64>,
+    intentional_draw: Option<u64>,
+    timed_win: Option<u64>,
+    timed_loss: Option<u64>,
+}
+
+impl From<ScoringSystemData> for ScoringSystem {
+    fn from(data: ScoringSystemData) -> Self {
+        let standard = Self::standard();
+        let mut scoring = Self::new(
+            data.win.unwrap_or(standard.win),
+            data.draw.unwrap_or(standard.draw),
+            data.loss.unwrap_or(standard.loss),
+        );
+        scoring.bye = data.bye.unwrap_or(scoring.bye);
+        scoring.intentional_draw = data.intentional_draw.unwrap_or(scoring.intentional_draw);
+        scoring.timed_win = data.timed_win.unwrap_or(scoring.timed_win);
+        scoring.timed_loss = data.timed_loss.unwrap_or(scoring.timed_loss);
+        scoring
+    }
+}
+
+impl Default for ScoringSystem {
+    #[inline]
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl ScoringSystem {
+    /// Creates the standard NSG scoring system
+    #[inline]
+    pub fn standard() -> Self {
+        Self::new(3, 1, 0)
+    }
+
+    /// Creates a custom scoring system with the given points for a win, a draw and a loss
+    ///
+    /// Byes score as wins, intentional draws as draws, and timed games as normal games
+    #[inline]
+    pub fn new(win: u64, draw: u64, loss: u64) -> Self {
+        Self {
+            win,
+            draw,
+            loss,
+            bye: win,
+            intentional_draw: draw,
+            timed_win: win,
+            timed_loss: loss,
+        }
+    }
+
+    /// Sets the points awarded for each game of a bye
+    #[inline]
+    pub fn with_bye_points(mut self, points: u64) -> Self {
+        self.bye = points;
+        self
+    }
+
+    /// Sets the points awarded to both players for each game of an intentional draw
+    ///
+    /// This is for events using a modified intentional draw rule
+    #[inline]
+    pub fn with_intentional_draw_points(mut self, points: u64) -> Self {
+        self.intentional_draw = points;
+        self
+    }
+
+    /// Sets the points awarded for games decided on agenda points when time was called
+    ///
+    /// This is for events using a modified timed win rule
+    #[inline]
+    pub fn with_timed_win_points(mut self, win: u64, loss: u64) -> Self {
+        self.timed_win = win;
+        self.timed_loss = loss;
+        self
+    }
+
+    /// Gets the points awarded for a single game result
+    #[inline]
+    pub fn get_points(&self, result: Result) -> u64 {
+        match result {
+            Result::Win => self.win,
+            Result::Draw => self.draw,
+            Result::Loss => self.loss,
+            Result::Bye => self.bye,
+            Result::IntentionalDraw => self.intentional_draw,
+            Result::TimedWin => self.timed_win,
+            Result::TimedLoss => self.timed_loss,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard() {
+        let scoring = ScoringSystem::default();
+        assert_eq!(scoring, ScoringSystem::standard());
+        assert_eq!(scoring.get_points(Result::Win), 3);
+        assert_eq!(scoring.get_points(Result::Draw), 1);
+        assert_eq!(scoring.get_points(Result::Loss), 0);
+        assert_eq!(scoring.get_points(Result::Bye), 3);
+        assert_eq!(scoring.get_points(Result::IntentionalDraw), 1);
+        assert_eq!(scoring.get_points(Result::TimedWin), 3);
+        assert_eq!(scoring.get_points(Result::TimedLoss), 0);
+    }
+
+    #[test]
+    fn modified() {
+        let scoring = ScoringSystem::standard()
+            .with_bye_points(2)
+            .with_intentional_draw_points(0)
+            .with_timed_win_points(2, 1);
+        assert_eq!(scoring.get_points(Result::Win), 3);
+        assert_eq!(scoring.get_points(Result::Bye), 2);
+        assert_eq!(scoring.get_points(Result::IntentionalDraw), 0);
+        assert_eq!(scoring.get_points(Result::TimedWin), 2);
+        assert_eq!(scoring.get_points(Result::TimedLoss), 1);
+    }
+
+    #[test]
+    fn partial_deserialization() {
+        let scoring: ScoringSystem =
+            serde_json::from_str(r#"{"win": 2, "draw": 1, "loss": 0}"#).unwrap();
+        assert_eq!(scoring, ScoringSystem::new(2, 1, 0));
+        assert_eq!(scoring.get_points(Result::Bye), 2);
+        assert_eq!(scoring.get_points(Result::TimedWin), 2);
+
+        let scoring: ScoringSystem = serde_json::from_str(r#"{"win": 2, "bye": 1}"#).unwrap();
+        assert_eq!(scoring, ScoringSystem::new(2, 1, 0).with_bye_points(1));
+
+        let scoring: ScoringSystem = serde_json::from_str("{}").unwrap();
+        assert_eq!(scoring, ScoringSystem::standard());
+    }
+}
@@ -0,0 +1,185 @@
+//! Standings related structures
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Player, Result};
+
+/// A player's game record
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    wins: u64,
+    losses: u64,
+    draws: u64,
+    byes: u64,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}-{}-{}",
+            self.wins, self.losses, self.draws, self.byes
+        )
+    }
+}
+
+impl Record {
+    /// Gets the number of games won
+    #[inline]
+    pub fn get_wins(&self) -> u64 {
+        self.wins
+    }
+
+    /// Gets the number of games lost
+    #[inline]
+    pub fn get_losses(&self) -> u64 {
+        self.losses
+    }
+
+    /// Gets the number of games drawn
+    #[inline]
+    pub fn get_draws(&self) -> u64 {
+        self.draws
+    }
+
+    /// Gets the number of bye games
+    #[inline]
+    pub fn get_byes(&self) -> u64 {
+        self.byes
+    }
+
+    /// Gets the total number of games, including byes
+    #[inline]
+    pub fn get_games(&self) -> u64 {
+        self.wins + self.losses + self.draws + self.byes
+    }
+
+    /// Gets the fraction of games won, not counting byes, or None if no games were played
+    #[inline]
+    pub fn get_win_rate(&self) -> Option<f64> {
+        let games = self.wins + self.losses + self.draws;
+        (games > 0).then(|| self.wins as f64 / games as f64)
+    }
+
+    /// Gets the record from the opponent's point of view
+    #[inline]
+    pub(crate) fn opposite(&self) -> Self {
+        Self {
+            wins: self.losses,
+            losses: self.wins,
+            ..*self
+        }
+    }
+
+    /// Adds a game result to the record
+    #[inline]
+    pub(crate) fn add(&mut self, result: Result) {
+        match result {
+            Result::Win | Result::TimedWin => self.wins += 1,
+            Result::Loss | Result::TimedLoss => self.losses += 1,
+            Result::Draw | Result::IntentionalDraw => self.draws += 1,
+            Result::Bye => self.byes += 1,
+        }
+    }
+}
+
+/// A player's position in the tournament standings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Standing {
+    rank: usize,
+    player: Player,
+
+    score: u64,
+    sos: f32,
+    esos: f32,
+
+    record: Record,
+    corp_record: Record,
+    runner_record: Record,
+
+    top_cut: bool,
+}
+
+impl Standing {
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub(crate) fn new(
+        rank: usize,
+        player: Player,
+        score: u64,
+        sos: f32,
+        esos: f32,
+        record: Record,
+        corp_record: Record,
+        runner_record: Record,
+        top_cut: bool,
+    ) -> Self {
+        Self {
+            rank,
+            player,
+            score,
+            sos,
+            esos,
+            record,
+            corp_record,
+            runner_record,
+            top_cut,
+        }
+    }
+
+    /// Gets the player's rank, starting from 1
+    #[inline]
+    pub fn get_rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Gets the player
+    #[inline]
+    pub fn get_player(&self) -> &Player {
+        &self.player
+    }
+
+    /// Gets the player's score
+    #[inline]
+    pub fn get_score(&self) -> u64 {
+        self.score
+    }
+
+    /// Gets the player's strength of schedule
+    #[inline]
+    pub fn get_sos(&self) -> f32 {
+        self.sos
+    }
+
+    /// Gets the player's extended strength of schedule
+    #[inline]
+    pub fn get_esos(&self) -> f32 {
+        self.esos
+    }
+
+    /// Gets the player's overall game record
+    #[inline]
+    pub fn get_record(&self) -> &Record {
+        &self.record
+    }
+
+    /// Gets the player's record playing the Corp
+    #[inline]
+    pub fn get_corp_record(&self) -> &Record {
+        &self.corp_record
+    }
+
+    /// Gets the player's record playing the Runner
+    #[inline]
+    pub fn get_runner_record(&self) -> &Record {
+        &self.runner_record
+    }
+
+    /// Checks if the player is currently placed to make the top cut
+    #[inline]
+    pub fn is_top_cut(&self) -> bool {
+        self.top_cut
+    }
+}
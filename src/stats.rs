@@ -0,0 +1,204 @@
+//! Player stats tracked by the pairings algorithms
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Record, Result, ScoringSystem, Side};
+
+/// Swiss style player stats
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct SwissStats {
+    pub(crate) score: u64,
+    pub(crate) rounds_played: u64,
+    pub(crate) byes: u64,
+
+    pub(crate) record: Record,
+    pub(crate) corp_record: Record,
+    pub(crate) runner_record: Record,
+
+    // ids of each non-bye opponent played, in round order
+    pub(crate) opponents: Vec<String>,
+
+    // tiebreakers are recalculated at the end of each round
+    pub(crate) sos: f32,
+    pub(crate) esos: f32,
+
+    // random final tiebreaker, drawn once when the player is added
+    pub(crate) tiebreak: u64,
+}
+
+impl AsRef<SwissStats> for SwissStats {
+    #[inline]
+    fn as_ref(&self) -> &SwissStats {
+        self
+    }
+}
+
+impl AsMut<SwissStats> for SwissStats {
+    #[inline]
+    fn as_mut(&mut self) -> &mut SwissStats {
+        self
+    }
+}
+
+impl SwissStats {
+    pub(crate) fn average_points_per_rounds(&self) -> f32 {
+        if self.rounds_played == 0 {
+            return 0.0;
+        }
+        self.score as f32 / self.rounds_played as f32
+    }
+
+    /// Adds a game result, played on the given side
+    pub(crate) fn add_result(
+        &mut self,
+        result: Result,
+        side: Option<Side>,
+        scoring: &ScoringSystem,
+    ) {
+        self.score += scoring.get_points(result);
+
+        self.record.add(result);
+        match side {
+            Some(Side::Corp) => self.corp_record.add(result),
+            Some(Side::Runner) => self.runner_record.add(result),
+            None => (),
+        }
+    }
+
+    /// Average of each opponent's average points per round
+    ///
+    /// Byes are not included
+    pub(crate) fn strength_of_schedule<T: AsRef<SwissStats>>(
+        &self,
+        stats: &HashMap<String, T>,
+    ) -> f32 {
+        if self.opponents.is_empty() {
+            return 0.0;
+        }
+
+        let total: f32 = self
+            .opponents
+            .iter()
+            .filter_map(|opponent| stats.get(opponent))
+            .map(|opponent| opponent.as_ref().average_points_per_rounds())
+            .sum();
+
+        total / self.opponents.len() as f32
+    }
+
+    /// Average of each opponent's strength of schedule
+    ///
+    /// Byes are not included
+    ///
+    /// This relies on the opponent's cached strength of schedule being up to date
+    pub(crate) fn extended_strength_of_schedule<T: AsRef<SwissStats>>(
+        &self,
+        stats: &HashMap<String, T>,
+    ) -> f32 {
+        if self.opponents.is_empty() {
+            return 0.0;
+        }
+
+        let total: f32 = self
+            .opponents
+            .iter()
+            .filter_map(|opponent| stats.get(opponent))
+            .map(|opponent| opponent.as_ref().sos)
+            .sum();
+
+        total / self.opponents.len() as f32
+    }
+
+    /// Recalculates the cached tiebreakers for every player
+    pub(crate) fn update_tiebreakers<T: AsRef<SwissStats> + AsMut<SwissStats>>(
+        stats: &mut HashMap<String, T>,
+    ) {
+        let sos = stats
+            .iter()
+            .map(|(id, player)| (id.clone(), player.as_ref().strength_of_schedule(stats)))
+            .collect::<Vec<_>>();
+        for (id, sos) in sos {
+            stats.get_mut(&id).unwrap().as_mut().sos = sos;
+        }
+
+        let esos = stats
+            .iter()
+            .map(|(id, player)| {
+                (
+                    id.clone(),
+                    player.as_ref().extended_strength_of_schedule(stats),
+                )
+            })
+            .collect::<Vec<_>>();
+        for (id, esos) in esos {
+            stats.get_mut(&id).unwrap().as_mut().esos = esos;
+        }
+    }
+}
+
+/// Single-sided swiss player stats
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct SingleSwissStats {
+    pub(crate) swiss: SwissStats,
+
+    // side played against each non-bye opponent, in round order
+    pub(crate) sides: Vec<Side>,
+}
+
+impl AsRef<SwissStats> for SingleSwissStats {
+    #[inline]
+    fn as_ref(&self) -> &SwissStats {
+        &self.swiss
+    }
+}
+
+impl AsMut<SwissStats> for SingleSwissStats {
+    #[inline]
+    fn as_mut(&mut self) -> &mut SwissStats {
+        &mut self.swiss
+    }
+}
+
+impl SingleSwissStats {
+    /// Number of games played as the Corp
+    #[inline]
+    pub(crate) fn corp_games(&self) -> usize {
+        self.sides
+            .iter()
+            .filter(|side| **side == Side::Corp)
+            .count()
+    }
+
+    /// Number of games played as the Runner
+    #[inline]
+    pub(crate) fn runner_games(&self) -> usize {
+        self.sides
+            .iter()
+            .filter(|side| **side == Side::Runner)
+            .count()
+    }
+
+    /// Corp games minus Runner games
+    #[inline]
+    pub(crate) fn side_balance(&self) -> i64 {
+        self.corp_games() as i64 - self.runner_games() as i64
+    }
+
+    /// Checks if this player has played the given opponent on the given side
+    #[inline]
+    pub(crate) fn has_played(&self, opponent: &str, side: Side) -> bool {
+        self.swiss
+            .opponents
+            .iter()
+            .zip(&self.sides)
+            .any(|(played, played_side)| played == opponent && *played_side == side)
+    }
+
+    /// Records a game against the given opponent
+    pub(crate) fn played(&mut self, opponent: impl Into<String>, side: Side) {
+        self.swiss.opponents.push(opponent.into());
+        self.sides.push(side);
+    }
+}